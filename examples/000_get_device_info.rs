@@ -1,22 +1,33 @@
 
-use clap::App;
+use clap::{App, Arg};
 
+use uhd_rs::probe::DeviceProbe;
+use uhd_rs::types::device_info::DeviceInfo;
 use uhd_rs::usrp::USRP;
 
 fn main() -> Result<(), &'static str> {
 
-	App::new("get_device_info")
+	let matches = App::new("get_device_info")
 		.version("0.1.0")
 		.author("John Stanford (johnwstanford@gmail.com)")
-		.about("A utility for getting USRP device information");
+		.about("A utility for getting USRP device information")
+		.arg(Arg::with_name("json")
+			.long("json")
+			.help("Print the full capability probe of each device as JSON"))
+		.get_matches();
 
-	let devices:Vec<String> = USRP::find("")?;
+	let devices:Vec<DeviceInfo> = USRP::find_devices("")?;
 	println!("Found {} USRP Device(s)", devices.len());
 
 	for device in devices {
-		println!("Getting info for device {}", device);
+		println!("Getting info for device {}", device.args);
 
-		let usrp = USRP::new(&device)?;
+		let usrp = USRP::open(&device)?;
+
+		if matches.is_present("json") {
+			println!("{}", DeviceProbe::read(&usrp)?.to_json()?);
+			continue;
+		}
 
 		let num_mboards:usize = usrp.num_mboards()?;
 		println!("Number of motherboard(s): {}", num_mboards);	
@@ -0,0 +1,42 @@
+use clap::{Arg, App};
+
+use uhd_rs::timing;
+use uhd_rs::timing::time_sync::{SyncSource, TimeSync};
+use uhd_rs::usrp::USRP;
+
+fn main() -> Result<(), &'static str> {
+
+    let matches = App::new("Multi-mboard time alignment for UHD_rs")
+        .version("0.1.0")
+        .author("John Stanford (johnwstanford@gmail.com)")
+        .about("Locks every mboard to a shared reference and aligns their times on one PPS edge")
+        .arg(Arg::with_name("source")
+            .long("source")
+            .help("internal, external, gpsdo or mimo")
+            .takes_value(true))
+        .arg(Arg::with_name("args")
+            .long("args")
+            .takes_value(true))
+        .get_matches();
+
+    let source:SyncSource = matches.value_of("source").unwrap_or("external").parse()?;
+
+    let mut usrp = USRP::new(matches.value_of("args").unwrap_or(""))?;
+    println!("Number of motherboard(s): {}", usrp.num_mboards()?);
+
+    let report = TimeSync::new(source).print_status(true).run(&mut usrp)?;
+    for mboard in report.mboards.iter() {
+        println!("Mboard {}: {} source, lock after {:?}, residual {:.3e} [sec]", mboard.mboard, mboard.source, mboard.lock_time, mboard.residual_secs);
+    }
+
+    let alignment = timing::align_all_mboards(&mut usrp, 0, 3)?;
+    for (attempt, last_pps) in alignment.misaligned.iter().enumerate() {
+        eprintln!("WARN: Mboards not aligned after attempt {} ({:?})", attempt + 1, last_pps);
+    }
+    if !alignment.aligned {
+        return Err("Unable to align time across all mboards");
+    }
+    println!("Aligned after {} attempt(s): {:?}", alignment.attempts, alignment.last_pps);
+
+    Ok(())
+}
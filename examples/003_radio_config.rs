@@ -0,0 +1,42 @@
+use clap::{Arg, App};
+
+use uhd_rs::radio_config::RadioConfig;
+use uhd_rs::usrp::USRP;
+
+fn main() -> Result<(), &'static str> {
+
+    let matches = App::new("Radio configuration profiles for UHD_rs")
+        .version("0.1.0")
+        .author("John Stanford (johnwstanford@gmail.com)")
+        .about("Applies a TOML or JSON radio profile and reports what the device coerced, or saves the current state as a profile")
+        .arg(Arg::with_name("apply")
+            .long("apply")
+            .help("Profile to apply")
+            .takes_value(true))
+        .arg(Arg::with_name("snapshot")
+            .long("snapshot")
+            .help("File to save the current device state to")
+            .takes_value(true))
+        .arg(Arg::with_name("args")
+            .long("args")
+            .takes_value(true))
+        .get_matches();
+
+    let mut usrp = USRP::new(matches.value_of("args").unwrap_or(""))?;
+
+    if let Some(path) = matches.value_of("apply") {
+        let requested = RadioConfig::load(path)?;
+        let actual = requested.apply(&mut usrp)?;
+        for path in requested.diff(&actual)? {
+            println!("Coerced: {}", path);
+        }
+        println!("{}", actual.to_toml()?);
+    }
+
+    if let Some(path) = matches.value_of("snapshot") {
+        RadioConfig::snapshot(&usrp)?.save(path)?;
+        println!("Saved current state to {}", path);
+    }
+
+    Ok(())
+}
@@ -1,10 +1,9 @@
 
 use std::f64::consts::PI;
-use std::ffi::CString;
 
 use clap::{Arg, App};
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
+use uhd_rs::types::tune_request::TuneRequestBuilder;
 use uhd_rs::usrp::USRP;
 
 fn main() -> Result<(), &'static str> {
@@ -44,18 +43,9 @@ fn main() -> Result<(), &'static str> {
 
 	let channel = 0;
 
-	let args = CString::new("").unwrap();
-
 	let mut usrp = USRP::new("")?;
 
-	let tune_request = TuneRequest {
-	    target_freq:    freq,						// Target frequency for RF chain in Hz
-	    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-	    rf_freq: 		0.0,						// RF frequency in Hz
-	    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-	    dsp_freq:		0.0,						// DSP frequency in Hz
-	    args:args.as_ptr()							// Key-value pairs delimited by commas		
-	};
+	let tune_request = TuneRequestBuilder::new(freq);
 
 	println!("Setting TX rate: {:.2e}...", rate);
 	usrp.set_tx_rate(rate, channel)?;
@@ -68,7 +58,7 @@ fn main() -> Result<(), &'static str> {
 	println!("Actual TX Gain: {:.2} dB...", usrp.get_tx_gain(channel, "")?);
 
 	println!("Setting TX frequency: {:.3} [MHz]...", tune_request.target_freq / 1.0e6);
-	let _tune_result = usrp.set_tx_freq(&tune_request, channel)?;
+	let _tune_result = usrp.tune_tx(&tune_request, channel)?;
 
 	println!("Actual TX frequency: {:.3} [MHz]...", usrp.get_tx_freq(channel)? / 1.0e6);
 
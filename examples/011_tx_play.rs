@@ -0,0 +1,69 @@
+use std::sync::atomic::AtomicBool;
+
+use clap::{Arg, App};
+
+use uhd_rs::player::{Player, Repeat};
+use uhd_rs::types::tune_request::TuneRequestBuilder;
+use uhd_rs::usrp::USRP;
+
+fn main() -> Result<(), &'static str> {
+
+    let matches = App::new("TX playback for UHD_rs")
+        .version("0.1.0")
+        .author("John Stanford (johnwstanford@gmail.com)")
+        .about("Transmits a raw sc16/fc32 or SigMF recording")
+        .arg(Arg::with_name("filename")
+            .short("f").long("filename")
+            .help("Recording to play; .sigmf-meta/.sigmf-data, .fc32 or raw sc16")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("sample_rate_sps")
+            .short("s").long("sample_rate_sps")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("freq_hz")
+            .long("freq_hz")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("gain_db")
+            .long("gain_db")
+            .takes_value(true))
+        .arg(Arg::with_name("scale")
+            .long("scale")
+            .help("Amplitude scale applied to every sample")
+            .takes_value(true))
+        .arg(Arg::with_name("repeat")
+            .long("repeat")
+            .help("Number of times to play the file; 0 plays until stopped")
+            .takes_value(true))
+        .arg(Arg::with_name("args")
+            .long("args")
+            .takes_value(true))
+        .get_matches();
+
+    let filename = matches.value_of("filename").unwrap();
+    let tx_freq = matches.value_of("freq_hz").unwrap().parse().unwrap();
+    let tx_rate = matches.value_of("sample_rate_sps").unwrap().parse().unwrap();
+    let tx_gain = matches.value_of("gain_db").unwrap_or("0.0").parse().unwrap();
+    let scale = matches.value_of("scale").unwrap_or("1.0").parse().unwrap();
+    let repeat = match matches.value_of("repeat").unwrap_or("1").parse().unwrap() {
+        0 => Repeat::Forever,
+        n => Repeat::Times(n),
+    };
+
+    let mut usrp = USRP::new(matches.value_of("args").unwrap_or(""))?;
+    usrp.set_tx_rate(tx_rate, 0)?;
+    usrp.set_tx_gain(tx_gain, 0, "")?;
+    usrp.tune_tx(&TuneRequestBuilder::new(tx_freq), 0)?;
+
+    let player = if filename.contains(".sigmf") {
+        Player::from_sigmf(filename)?
+    } else if filename.ends_with(".fc32") {
+        Player::from_fc32_file(filename)?
+    } else {
+        Player::from_sc16_file(filename)?
+    };
+
+    let (now_full, now_frac) = usrp.get_time_now(0)?;
+    let report = player.scale(scale).repeat(repeat).start_time((now_full + 1, now_frac)).run(&mut usrp, &AtomicBool::new(false))?;
+    println!("{:?}", report);
+
+    Ok(())
+}
@@ -1,9 +1,8 @@
 
 use std::f64::consts::PI;
-use std::ffi::CString;
 use std::time::Duration;
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
+use uhd_rs::types::tune_request::TuneRequestBuilder;
 use uhd_rs::usrp::USRP;
 
 fn main() -> Result<(), &'static str> {
@@ -22,25 +21,16 @@ fn main() -> Result<(), &'static str> {
 
 	let channel = 0;
 
-	let empty_args = CString::new("").unwrap();
-
 	let mut usrp = USRP::new("")?;
 
 	println!("TX num channels: {}", usrp.tx_num_channels()?);
 
 	// Set up TX
-	let tune_request0 = TuneRequest {
-	    target_freq:    tx_freq0,					// Target frequency for RF chain in Hz
-	    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-	    rf_freq: 		0.0,						// RF frequency in Hz
-	    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-	    dsp_freq:		0.0,						// DSP frequency in Hz
-	    args:empty_args.as_ptr()					// Key-value pairs delimited by commas		
-	};
+	let tune_request0 = TuneRequestBuilder::new(tx_freq0);
 
 	usrp.set_tx_rate(tx_rate, channel)?;
 	usrp.set_tx_gain(tx_gain, channel, "")?;
-	let tx_tune_result0 = usrp.set_tx_freq(&tune_request0, channel)?;
+	let tx_tune_result0 = usrp.tune_tx(&tune_request0, channel)?;
 	println!("{:#?}", tx_tune_result0);
 
 	println!("TX: {:.2e} [sps], {:.1} [dB], {:.3} [MHz]", usrp.get_tx_rate(channel)?, usrp.get_tx_gain(channel, "")?, usrp.get_tx_freq(channel)? / 1.0e6);
@@ -78,16 +68,9 @@ fn main() -> Result<(), &'static str> {
 
 	std::thread::sleep(Duration::from_millis(4000));
 
-	let tune_request1 = TuneRequest {
-	    target_freq:    tx_freq1,					// Target frequency for RF chain in Hz
-	    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-	    rf_freq: 		0.0,						// RF frequency in Hz
-	    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-	    dsp_freq:		0.0,						// DSP frequency in Hz
-	    args:empty_args.as_ptr()					// Key-value pairs delimited by commas		
-	};
+	let tune_request1 = TuneRequestBuilder::new(tx_freq1);
 
-	let tx_tune_result1 = usrp.set_tx_freq(&tune_request1, channel)?;
+	let tx_tune_result1 = usrp.tune_tx(&tune_request1, channel)?;
 	println!("{:#?}", tx_tune_result1);
 
 	println!("Waiting on TX thread");
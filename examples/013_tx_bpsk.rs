@@ -1,8 +1,7 @@
 
-use std::ffi::CString;
 use std::time::{Duration, Instant};
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
+use uhd_rs::types::tune_request::TuneRequestBuilder;
 use uhd_rs::usrp::USRP;
 
 const BURST_LEN: Duration = Duration::from_secs(4);
@@ -21,25 +20,16 @@ fn main() -> Result<(), &'static str> {
 
     let channel = 0;
 
-    let empty_args = CString::new("").unwrap();
-
     let mut usrp = USRP::new("")?;
 
     println!("TX num channels: {}", usrp.tx_num_channels()?);
 
     // Set up TX
-    let tune_request0 = TuneRequest {
-        target_freq:    tx_freq0,					// Target frequency for RF chain in Hz
-        rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-        rf_freq: 		0.0,						// RF frequency in Hz
-        dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-        dsp_freq:		0.0,						// DSP frequency in Hz
-        args:empty_args.as_ptr()					// Key-value pairs delimited by commas
-    };
+    let tune_request0 = TuneRequestBuilder::new(tx_freq0);
 
     usrp.set_tx_rate(tx_rate, channel)?;
     usrp.set_tx_gain(tx_gain, channel, "")?;
-    let tx_tune_result0 = usrp.set_tx_freq(&tune_request0, channel)?;
+    let tx_tune_result0 = usrp.tune_tx(&tune_request0, channel)?;
     println!("{:#?}", tx_tune_result0);
 
     println!("TX: {:.2e} [sps], {:.1} [dB], {:.3} [MHz]", usrp.get_tx_rate(channel)?, usrp.get_tx_gain(channel, "")?, usrp.get_tx_freq(channel)? / 1.0e6);
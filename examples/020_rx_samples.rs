@@ -1,10 +1,10 @@
 
 use clap::{Arg, App};
 
+use uhd_rs::io::sigmf::{Capture, Global, SigMfWriter};
 use uhd_rs::usrp::USRP;
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
-use std::ffi::CString;
+use uhd_rs::types::tune_request::TuneRequestBuilder;
 
 fn main() -> Result<(), &'static str> {
 
@@ -32,6 +32,9 @@ fn main() -> Result<(), &'static str> {
 			.long("time_sec")
 			.help("Time to capture [seconds]")
 			.takes_value(true).required(true))
+		.arg(Arg::with_name("sigmf")
+			.long("sigmf")
+			.help("Write a SigMF recording instead of a raw file"))
 		.get_matches();
 
 	let rx_freq = matches.value_of("freq_hz").unwrap().parse().unwrap();
@@ -47,20 +50,11 @@ fn main() -> Result<(), &'static str> {
 	println!("Clock source: {}", usrp.get_clock_source(0)?);
 
 	// Set up RX
-	let empty_args = CString::new("").unwrap();
-
-	let tune_request = TuneRequest {
-		target_freq:    rx_freq,					// Target frequency for RF chain in Hz
-		rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-		rf_freq: 		0.0,						// RF frequency in Hz
-		dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-		dsp_freq:		0.0,						// DSP frequency in Hz
-		args:empty_args.as_ptr()					// Key-value pairs delimited by commas
-	};
+	let tune_request = TuneRequestBuilder::new(rx_freq);
 
 	usrp.set_rx_rate(rx_rate, channel)?;
 	usrp.set_rx_gain(rx_gain, channel, "")?;
-	let _rx_tune_result = usrp.set_rx_freq(&tune_request, channel)?;
+	let _rx_tune_result = usrp.tune_rx(&tune_request, channel)?;
 
 	println!("RX: {:.2e} [sps], {:.1} [dB], {:.3} [MHz]", usrp.get_rx_rate(channel)?, usrp.get_rx_gain(channel, "")?, usrp.get_rx_freq(channel)? / 1.0e6);
 
@@ -74,7 +68,14 @@ fn main() -> Result<(), &'static str> {
 		.map(|s| s.to_owned())
 		.unwrap_or(format!("rx_A0_{:.2}MHz_{}dB_{}Msps.bin", rx_freq/1.0e6, rx_gain as usize, (rx_rate/1.0e6) as usize));
 
-	uhd_rs::io::write_sc16_to_file(filename, &rx_buffer)?;
+	if matches.is_present("sigmf") {
+		let mut writer = SigMfWriter::create(filename.trim_end_matches(".bin"), Global::for_rx(&usrp, &[channel])?)?;
+		writer.start_capture(Capture::for_rx(&usrp, channel)?);
+		writer.write_sc16_at(&rx_buffer, rx_time_spec)?;
+		writer.finish()?;
+	} else {
+		uhd_rs::io::write_sc16_to_file(filename, &rx_buffer)?;
+	}
 
  	Ok(())
 }
@@ -0,0 +1,64 @@
+use std::sync::atomic::AtomicBool;
+
+use clap::{Arg, App};
+
+use uhd_rs::recorder::Recorder;
+use uhd_rs::types::tune_request::TuneRequestBuilder;
+use uhd_rs::usrp::USRP;
+
+fn main() -> Result<(), &'static str> {
+
+    let matches = App::new("Long-running RX recorder for UHD_rs")
+        .version("0.1.0")
+        .author("John Stanford (johnwstanford@gmail.com)")
+        .about("Streams RX samples to rotating SigMF files")
+        .arg(Arg::with_name("dir")
+            .long("dir")
+            .help("Output directory")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("sample_rate_sps")
+            .short("s").long("sample_rate_sps")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("freq_hz")
+            .long("freq_hz")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("gain_db")
+            .long("gain_db")
+            .takes_value(true))
+        .arg(Arg::with_name("time_sec")
+            .long("time_sec")
+            .help("Time to record [seconds]")
+            .takes_value(true).required(true))
+        .arg(Arg::with_name("file_sec")
+            .long("file_sec")
+            .help("Start a new file after this many seconds of samples")
+            .takes_value(true))
+        .arg(Arg::with_name("args")
+            .long("args")
+            .takes_value(true))
+        .get_matches();
+
+    let rx_freq = matches.value_of("freq_hz").unwrap().parse().unwrap();
+    let rx_rate = matches.value_of("sample_rate_sps").unwrap().parse().unwrap();
+    let rx_gain = matches.value_of("gain_db").unwrap_or("60.0").parse().unwrap();
+    let rx_time = matches.value_of("time_sec").unwrap().parse().unwrap();
+    let file_time = matches.value_of("file_sec").unwrap_or("60.0").parse().unwrap();
+
+    let mut usrp = USRP::new(matches.value_of("args").unwrap_or(""))?;
+    usrp.set_rx_rate(rx_rate, 0)?;
+    usrp.set_rx_gain(rx_gain, 0, "")?;
+    usrp.tune_rx(&TuneRequestBuilder::new(rx_freq), 0)?;
+
+    let recorder = Recorder::new(matches.value_of("dir").unwrap(), "rx")
+        .rotate_secs(file_time)
+        .max_secs(rx_time)
+        .preallocate(true);
+
+    let summary = recorder.run(&mut usrp, &AtomicBool::new(false))?;
+    for file in summary.files.iter() {
+        println!("{}: {} samples, {} overflows", file.data_path.display(), file.samples, file.overflows);
+    }
+    println!("Stopped: {:?}", summary.stop_reason);
+
+    Ok(())
+}
@@ -1,10 +1,12 @@
 
 use clap::{Arg, App};
 
-use uhd_rs::usrp::{StreamCmd, StreamMode, USRP};
+use uhd_rs::lo_sharing::LoSharing;
+use uhd_rs::recorder::Recorder;
+use uhd_rs::usrp::USRP;
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
-use std::ffi::CString;
+use uhd_rs::types::tune_request::TuneRequestBuilder;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
 const ALL_CHANS: [usize; 4] = [0, 1, 2, 3];
@@ -40,10 +42,8 @@ fn main() -> Result<(), &'static str> {
     let rx_gain = matches.value_of("gain_db").unwrap_or("93.0").parse().unwrap();
     let rx_time = matches.value_of("time_sec").unwrap_or("0.002").parse::<f64>().unwrap();
 
-    let num_rx_samps = (rx_time * rx_rate) as usize;
-
     let mut usrp = USRP::new(matches.value_of("args").unwrap_or(""))?;
-    let mut rx_subdev = usrp.get_subdev_spec(0)?;
+    let rx_subdev = usrp.get_subdev_spec(0)?;
     let n_rx_subdevs = rx_subdev.len()?;
 
     println!("Num subdevs: {} ({})", n_rx_subdevs, rx_subdev.to_string()?);
@@ -58,21 +58,12 @@ fn main() -> Result<(), &'static str> {
     std::thread::sleep(Duration::from_millis(100));
 
     // Set up RX
-    let empty_args = CString::new("").unwrap();
-
-    let tune_request = TuneRequest {
-        target_freq:    rx_freq,					// Target frequency for RF chain in Hz
-        rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-        rf_freq: 		rx_freq,	    			// RF frequency in Hz
-        dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-        dsp_freq:		0.0,						// DSP frequency in Hz
-        args:empty_args.as_ptr()					// Key-value pairs delimited by commas
-    };
+    let tune_request = TuneRequestBuilder::new(rx_freq);
 
     for channel in ALL_CHANS.iter() {
         usrp.set_rx_rate(rx_rate, *channel)?;
         usrp.set_rx_gain(rx_gain, *channel, "")?;
-        let _rx_tune_result = usrp.set_rx_freq(&tune_request, *channel)?;
+        let _rx_tune_result = usrp.tune_rx(&tune_request, *channel)?;
 
         let rx_rate_rb = usrp.get_rx_rate(*channel)?;
         let rx_gain_rb = usrp.get_rx_gain(*channel, "")?;
@@ -86,11 +77,14 @@ fn main() -> Result<(), &'static str> {
     
     std::thread::sleep(Duration::from_millis(50));
 
-    usrp.set_rx_lo_export_enabled(true, "all", EXPORT_CHAN)?;
-    usrp.set_rx_lo_source("internal", "all", EXPORT_CHAN)?;
-    usrp.set_rx_lo_source("companion", "all", EXPORT_CHAN ^ 1)?;
-    usrp.set_rx_lo_source("external", "all", EXPORT_CHAN ^ 2)?;
-    usrp.set_rx_lo_source("external", "all", EXPORT_CHAN ^ 3)?;
+    let lo_sharing = LoSharing::twinrx(EXPORT_CHAN, &[EXPORT_CHAN ^ 1, EXPORT_CHAN ^ 2, EXPORT_CHAN ^ 3]);
+    let lo_report = lo_sharing.apply(&mut usrp)?;
+    for status in lo_report.channels.iter() {
+        println!("CH{}: LO source {} (expected {}), LO freq {:?}", status.chan, status.reported_source, status.expected_source, status.lo_freq);
+    }
+    if !lo_report.phase_coherent_ready() {
+        eprintln!("WARN: LO sharing isn't ready for phase-coherent operation");
+    }
 
     std::thread::sleep(Duration::from_millis(50));
 
@@ -99,7 +93,7 @@ fn main() -> Result<(), &'static str> {
     
     usrp.set_command_time(now_full+1, now_frac, 0)?;
     for channel in ALL_CHANS.iter() {
-        let _rx_tune_result = usrp.set_rx_freq(&tune_request, *channel)?;
+        let _rx_tune_result = usrp.tune_rx(&tune_request, *channel)?;
         println!("CH{}: Timed command complete", channel);
     }
     usrp.clear_command_time(0)?;
@@ -114,37 +108,20 @@ fn main() -> Result<(), &'static str> {
 
     // std::thread::sleep(Duration::from_secs(4));
 
-    let mut rx_streamer = usrp.get_rx_stream("", &ALL_CHANS)?;
-
-    let (now_full, now_frac) = usrp.get_time_now(0)?;
-
-    let stream_cmd_start = StreamCmd{
-        stream_mode: StreamMode::NumSampsAndDone,
-        num_samps: num_rx_samps,
-        stream_now: false,
-        time_spec_full_secs: now_full + 1,
-        time_spec_frac_secs: now_frac,
-    };
-    rx_streamer.stream(&stream_cmd_start)?;
-
-    let mut rx_buffer0: Vec<(i16, i16)> = vec![(0,0); num_rx_samps];
-    let mut rx_buffer1: Vec<(i16, i16)> = vec![(0,0); num_rx_samps];
-    let mut rx_buffer2: Vec<(i16, i16)> = vec![(0,0); num_rx_samps];
-    let mut rx_buffer3: Vec<(i16, i16)> = vec![(0,0); num_rx_samps];
-
-    std::thread::sleep(Duration::from_secs(1));
-
-    let (num_samps, rx_time_spec) = rx_streamer.recv_one_multi_chan(
-        &mut [&mut rx_buffer0, &mut rx_buffer1, &mut rx_buffer2, &mut rx_buffer3]
-    )?;
-
-    println!("{} samples received at {:?}", num_samps, rx_time_spec);
-
-    if num_samps > 0 {
-        for (ch, buff) in vec![("A0", &rx_buffer0), ("A1", &rx_buffer1), ("B0", &rx_buffer2), ("B1", &rx_buffer3)] {
-            let filename = format!("twinrx_{}_{:.2}MHz_{}dB_{}Msps.bin", ch, rx_freq/1.0e6, rx_gain as usize, (rx_rate/1.0e6) as usize);
-            uhd_rs::io::write_sc16_to_file(filename, buff)?;
-        }
+    // All four channels start together one second from now and go to one SigMF recording per channel, with the
+    // subdev and LO routing of each channel in its metadata
+    let recorder = Recorder::new(".", &format!("twinrx_{:.2}MHz", rx_freq/1.0e6))
+        .chans(&ALL_CHANS)
+        .start_delay(1.0)
+        .max_secs(rx_time)
+        .min_free_bytes(0);
+    let summary = recorder.run(&mut usrp, &AtomicBool::new(false))?;
+
+    for file in summary.files.iter() {
+        println!("CH{:?}: {} samples from {:?} to {:?} -> {}", file.chans, file.samples, file.start, file.end, file.data_path.display());
+    }
+    if summary.misalignments() > 0 || summary.overflows() > 0 {
+        eprintln!("WARN: {} misalignments, {} overflows", summary.misalignments(), summary.overflows());
     }
 
     Ok(())
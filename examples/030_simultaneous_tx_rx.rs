@@ -1,8 +1,7 @@
 
 use std::f64::consts::PI;
-use std::ffi::CString;
 
-use uhd_rs::types::{TuneRequest, TuneRequestPolicy};
+use uhd_rs::types::tune_request::TuneRequestBuilder;
 use uhd_rs::usrp::USRP;
 
 fn main() -> Result<(), &'static str> {
@@ -28,8 +27,6 @@ fn main() -> Result<(), &'static str> {
 
 	let channel = 0;
 
-	let empty_args = CString::new("").unwrap();
-
 	let mut usrp = USRP::new("")?;
 
 	println!("USRP num motherboards: {}", usrp.num_mboards()?);
@@ -38,36 +35,22 @@ fn main() -> Result<(), &'static str> {
 
 	{
 		// Set up TX
-		let tune_request = TuneRequest {
-		    target_freq:    tx_freq,					// Target frequency for RF chain in Hz
-		    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-		    rf_freq: 		0.0,						// RF frequency in Hz
-		    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-		    dsp_freq:		0.0,						// DSP frequency in Hz
-		    args:empty_args.as_ptr()					// Key-value pairs delimited by commas		
-		};
+		let tune_request = TuneRequestBuilder::new(tx_freq);
 
 		usrp.set_tx_rate(tx_rate, channel)?;
 		usrp.set_tx_gain(tx_gain, channel, "")?;
-		let _tx_tune_result = usrp.set_tx_freq(&tune_request, channel)?;
+		let _tx_tune_result = usrp.tune_tx(&tune_request, channel)?;
 
 		println!("TX: {:.2e} [sps], {:.1} [dB], {:.3} [MHz]", usrp.get_tx_rate(channel)?, usrp.get_tx_gain(channel, "")?, usrp.get_tx_freq(channel)? / 1.0e6);
 	}
 
 	{
 		// Set up RX
-		let tune_request = TuneRequest {
-		    target_freq:    rx_freq,					// Target frequency for RF chain in Hz
-		    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-		    rf_freq: 		0.0,						// RF frequency in Hz
-		    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-		    dsp_freq:		0.0,						// DSP frequency in Hz
-		    args:empty_args.as_ptr()							// Key-value pairs delimited by commas		
-		};
+		let tune_request = TuneRequestBuilder::new(rx_freq);
 
 		usrp.set_rx_rate(rx_rate, channel)?;
 		usrp.set_rx_gain(rx_gain, channel, "")?;		
-		let _rx_tune_result = usrp.set_rx_freq(&tune_request, channel)?;
+		let _rx_tune_result = usrp.tune_rx(&tune_request, channel)?;
 
 		println!("RX: {:.2e} [sps], {:.1} [dB], {:.3} [MHz]", usrp.get_rx_rate(channel)?, usrp.get_rx_gain(channel, "")?, usrp.get_rx_freq(channel)? / 1.0e6);
 	}
@@ -110,9 +93,7 @@ fn main() -> Result<(), &'static str> {
 	println!("RX complete at USRP time {:?}", usrp.get_time_now(0));
 
 	let filename:String = format!("output_{:.2}MHz_{:.1}Msps_gain{:.1}dB_sc16.dat", rx_freq/1.0e6, rx_rate/1.0e6, rx_gain);
-	let rx_buffer_ptr:*const u8 = &rx_buffer[0] as *const (i16,i16) as *const u8;
-	let rx_bytes:&[u8] = unsafe{ std::slice::from_raw_parts(rx_buffer_ptr, rx_buffer.len() * std::mem::size_of::<(i16,i16)>()) };
-	std::fs::write(&filename, rx_bytes).map_err(|_| "Unable to save output file")?;
+	uhd_rs::io::write_sc16_to_file(&filename, &rx_buffer)?;
 
 	println!("Waiting on TX thread");
 	tx_handle.join().unwrap();
@@ -1,3 +1,11 @@
+use std::ffi::CStr;
+
+// Reads a string UHD wrote into `buff`, looking for the NUL only within the buffer
+pub fn cstr_from_buff(buff:&[u8]) -> Result<String, &'static str> {
+    let cstr = CStr::from_bytes_until_nul(buff).map_err(|_| "String from UHD isn't NUL-terminated")?;
+    cstr.to_str().map(|s| s.to_owned()).map_err(|_| "String from UHD isn't valid UTF-8")
+}
+
 /// # Safety
 ///
 /// This function requires `ptr` to point to an allocated C string
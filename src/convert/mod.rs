@@ -0,0 +1,369 @@
+use std::borrow::Cow;
+
+mod simd;
+
+#[cfg(test)]
+mod tests;
+
+// Conversions between host sample formats that don't assume the host's byte order or how Rust lays out tuples.
+// Everything here works on caller-provided buffers; only the `Cow` helpers allocate, and only when the bytes
+// can't be used in place.
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endian { Little, Big }
+
+impl Endian {
+    #[cfg(target_endian = "little")]
+    pub const NATIVE:Endian = Endian::Little;
+    #[cfg(target_endian = "big")]
+    pub const NATIVE:Endian = Endian::Big;
+}
+
+// Integer formats by the range of values they carry. sc12 is unpacked: one sign-extended value per i16.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IntFormat { Sc8, Sc12, Sc16 }
+
+impl IntFormat {
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            IntFormat::Sc8 => 8,
+            IntFormat::Sc12 => 12,
+            IntFormat::Sc16 => 16,
+        }
+    }
+
+    pub fn min(&self) -> i32 { -(1 << (self.bits() - 1)) }
+    pub fn max(&self) -> i32 { (1 << (self.bits() - 1)) - 1 }
+
+}
+
+/// Types that can be viewed as bytes and that bytes can be viewed as (given the right length and alignment).
+///
+/// # Safety
+///
+/// Implementors must have no padding and no invalid bit patterns.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => { $(unsafe impl Pod for $t {})* };
+}
+
+impl_pod!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+// One component of a complex sample, readable and writable in either byte order
+pub trait Sample: Pod + Default {
+    const SIZE:usize;
+    fn read(bytes:&[u8], endian:Endian) -> Self;
+    fn write(self, bytes:&mut [u8], endian:Endian);
+}
+
+macro_rules! impl_sample {
+    ($($t:ty),*) => { $(
+        impl Sample for $t {
+            const SIZE:usize = std::mem::size_of::<$t>();
+
+            fn read(bytes:&[u8], endian:Endian) -> Self {
+                let mut raw = [0u8; std::mem::size_of::<$t>()];
+                raw.copy_from_slice(&bytes[..Self::SIZE]);
+                match endian {
+                    Endian::Little => <$t>::from_le_bytes(raw),
+                    Endian::Big => <$t>::from_be_bytes(raw),
+                }
+            }
+
+            fn write(self, bytes:&mut [u8], endian:Endian) {
+                let raw = match endian {
+                    Endian::Little => self.to_le_bytes(),
+                    Endian::Big => self.to_be_bytes(),
+                };
+                bytes[..Self::SIZE].copy_from_slice(&raw);
+            }
+        }
+    )* };
+}
+
+impl_sample!(i8, i16, f32, f64);
+
+// Integer sample containers: i8 holds sc8, i16 holds sc8, sc12 or sc16
+pub trait Int: Sample {
+    fn to_i32(self) -> i32;
+    fn from_i32(x:i32) -> Self;     // `x` is already in range
+    fn as_i16s(s:&[Self]) -> Option<&[i16]> { let _ = s; None }
+    fn as_i16s_mut(s:&mut [Self]) -> Option<&mut [i16]> { let _ = s; None }
+}
+
+impl Int for i8 {
+    fn to_i32(self) -> i32 { self as i32 }
+    fn from_i32(x:i32) -> Self { x as i8 }
+}
+
+impl Int for i16 {
+    fn to_i32(self) -> i32 { self as i32 }
+    fn from_i32(x:i32) -> Self { x as i16 }
+    fn as_i16s(s:&[Self]) -> Option<&[i16]> { Some(s) }
+    fn as_i16s_mut(s:&mut [Self]) -> Option<&mut [i16]> { Some(s) }
+}
+
+pub trait Float: Sample {
+    fn to_f64(self) -> f64;
+    fn from_f64(x:f64) -> Self;
+    fn as_f32s(s:&[Self]) -> Option<&[f32]> { let _ = s; None }
+    fn as_f32s_mut(s:&mut [Self]) -> Option<&mut [f32]> { let _ = s; None }
+}
+
+impl Float for f32 {
+    fn to_f64(self) -> f64 { self as f64 }
+    fn from_f64(x:f64) -> Self { x as f32 }
+    fn as_f32s(s:&[Self]) -> Option<&[f32]> { Some(s) }
+    fn as_f32s_mut(s:&mut [Self]) -> Option<&mut [f32]> { Some(s) }
+}
+
+impl Float for f64 {
+    fn to_f64(self) -> f64 { self }
+    fn from_f64(x:f64) -> Self { x }
+}
+
+pub fn bytes_of<T: Pod>(s:&[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(s.as_ptr() as *const u8, std::mem::size_of_val(s)) }
+}
+
+pub fn bytes_of_mut<T: Pod>(s:&mut [T]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(s.as_mut_ptr() as *mut u8, std::mem::size_of_val(s)) }
+}
+
+fn check_cast<T: Pod>(bytes:&[u8]) -> Result<usize, &'static str> {
+    if bytes.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+        return Err("Bytes aren't aligned for the target type");
+    }
+    let n = bytes.len() / std::mem::size_of::<T>();
+    if n * std::mem::size_of::<T>() != bytes.len() {
+        return Err("Bytes don't hold a whole number of values");
+    }
+    Ok(n)
+}
+
+// Views bytes as values in the host's byte order
+pub fn cast_slice<T: Pod>(bytes:&[u8]) -> Result<&[T], &'static str> {
+    let n = check_cast::<T>(bytes)?;
+    Ok(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const T, n) })
+}
+
+pub fn cast_slice_mut<T: Pod>(bytes:&mut [u8]) -> Result<&mut [T], &'static str> {
+    let n = check_cast::<T>(bytes)?;
+    Ok(unsafe { std::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut T, n) })
+}
+
+// Rust doesn't promise the field order of a tuple, so that's checked before (I, Q) pairs are treated as
+// interleaved I/Q. It's a constant, so the check compiles away.
+fn pairs_are_interleaved<T: Pod>() -> bool {
+    std::mem::size_of::<(T, T)>() == 2 * std::mem::size_of::<T>() && std::mem::offset_of!((T, T), 0) == 0
+}
+
+pub fn flatten<T: Pod>(iq:&[(T, T)]) -> Result<&[T], &'static str> {
+    if !pairs_are_interleaved::<T>() {
+        return Err("(I, Q) tuples aren't laid out as interleaved I/Q");
+    }
+    Ok(unsafe { std::slice::from_raw_parts(iq.as_ptr() as *const T, iq.len() * 2) })
+}
+
+pub fn flatten_mut<T: Pod>(iq:&mut [(T, T)]) -> Result<&mut [T], &'static str> {
+    if !pairs_are_interleaved::<T>() {
+        return Err("(I, Q) tuples aren't laid out as interleaved I/Q");
+    }
+    Ok(unsafe { std::slice::from_raw_parts_mut(iq.as_mut_ptr() as *mut T, iq.len() * 2) })
+}
+
+pub fn pairs<T: Pod>(interleaved:&[T]) -> Result<&[(T, T)], &'static str> {
+    if !pairs_are_interleaved::<T>() {
+        return Err("(I, Q) tuples aren't laid out as interleaved I/Q");
+    }
+    if interleaved.len() / 2 * 2 != interleaved.len() {
+        return Err("Interleaved I/Q needs an even number of values");
+    }
+    Ok(unsafe { std::slice::from_raw_parts(interleaved.as_ptr() as *const (T, T), interleaved.len() / 2) })
+}
+
+pub fn pairs_mut<T: Pod>(interleaved:&mut [T]) -> Result<&mut [(T, T)], &'static str> {
+    if !pairs_are_interleaved::<T>() {
+        return Err("(I, Q) tuples aren't laid out as interleaved I/Q");
+    }
+    if interleaved.len() / 2 * 2 != interleaved.len() {
+        return Err("Interleaved I/Q needs an even number of values");
+    }
+    Ok(unsafe { std::slice::from_raw_parts_mut(interleaved.as_mut_ptr() as *mut (T, T), interleaved.len() / 2) })
+}
+
+// Complex samples as bytes in the host's byte order, e.g. to hand to UHD
+pub fn iq_bytes<T: Pod>(iq:&[(T, T)]) -> Result<&[u8], &'static str> {
+    flatten(iq).map(bytes_of)
+}
+
+pub fn cast_iq<T: Pod>(bytes:&[u8]) -> Result<&[(T, T)], &'static str> {
+    pairs(cast_slice(bytes)?)
+}
+
+// `dst` has to be exactly `src.len() * T::SIZE` bytes
+pub fn encode<T: Sample>(src:&[T], dst:&mut [u8], endian:Endian) -> Result<(), &'static str> {
+    if dst.len() != src.len() * T::SIZE {
+        return Err("Byte buffer is the wrong size for these samples");
+    }
+    if endian == Endian::NATIVE {
+        dst.copy_from_slice(bytes_of(src));
+    } else {
+        for (x, b) in src.iter().zip(dst.chunks_exact_mut(T::SIZE)) {
+            x.write(b, endian);
+        }
+    }
+    Ok(())
+}
+
+pub fn decode<T: Sample>(src:&[u8], dst:&mut [T], endian:Endian) -> Result<(), &'static str> {
+    if src.len() != dst.len() * T::SIZE {
+        return Err("Byte buffer is the wrong size for these samples");
+    }
+    if endian == Endian::NATIVE {
+        bytes_of_mut(dst).copy_from_slice(src);
+    } else {
+        for (x, b) in dst.iter_mut().zip(src.chunks_exact(T::SIZE)) {
+            *x = T::read(b, endian);
+        }
+    }
+    Ok(())
+}
+
+pub fn encode_iq<T: Sample>(src:&[(T, T)], dst:&mut [u8], endian:Endian) -> Result<(), &'static str> {
+    encode(flatten(src)?, dst, endian)
+}
+
+pub fn decode_iq<T: Sample>(src:&[u8], dst:&mut [(T, T)], endian:Endian) -> Result<(), &'static str> {
+    decode(src, flatten_mut(dst)?, endian)
+}
+
+// Little-endian interleaved I/Q, the layout of raw sample files; borrowed on little-endian hosts
+pub fn iq_to_le_bytes<T: Sample>(iq:&[(T, T)]) -> Cow<'_, [u8]> {
+    match iq_bytes(iq) {
+        Ok(bytes) if Endian::NATIVE == Endian::Little => Cow::Borrowed(bytes),
+        _ => {
+            let mut bytes = vec![0u8; iq.len() * 2 * T::SIZE];
+            for ((i, q), b) in iq.iter().zip(bytes.chunks_exact_mut(2 * T::SIZE)) {
+                i.write(&mut b[..T::SIZE], Endian::Little);
+                q.write(&mut b[T::SIZE..], Endian::Little);
+            }
+            Cow::Owned(bytes)
+        }
+    }
+}
+
+pub fn iq_from_le_bytes<T: Sample>(bytes:&[u8]) -> Result<Cow<'_, [(T, T)]>, &'static str> {
+    if let (Endian::Little, Ok(iq)) = (Endian::NATIVE, cast_iq::<T>(bytes)) {
+        return Ok(Cow::Borrowed(iq));
+    }
+    let n = bytes.len() / (2 * T::SIZE);
+    if n * 2 * T::SIZE != bytes.len() {
+        return Err("Bytes don't hold a whole number of samples");
+    }
+    let mut iq = vec![(T::default(), T::default()); n];
+    for ((i, q), b) in iq.iter_mut().zip(bytes.chunks_exact(2 * T::SIZE)) {
+        *i = T::read(&b[..T::SIZE], Endian::Little);
+        *q = T::read(&b[T::SIZE..], Endian::Little);
+    }
+    Ok(Cow::Owned(iq))
+}
+
+fn check_lens(src:usize, dst:usize) -> Result<(), &'static str> {
+    match src == dst {
+        true => Ok(()),
+        false => Err("Source and destination have different lengths"),
+    }
+}
+
+fn check_container<I: Int>(format:IntFormat) -> Result<(), &'static str> {
+    match I::SIZE as u32 * 8 >= format.bits() {
+        true => Ok(()),
+        false => Err("Integer type is too small for this format"),
+    }
+}
+
+// `format.max()` maps to `fullscale`, the way UHD scales sc16 to fc32 with a fullscale of 1.0
+pub fn int_to_float<I: Int, F: Float>(src:&[I], format:IntFormat, dst:&mut [F], fullscale:f64) -> Result<(), &'static str> {
+    check_lens(src.len(), dst.len())?;
+    check_container::<I>(format)?;
+    let gain = fullscale / format.max() as f64;
+    if let (Some(src), Some(dst)) = (I::as_i16s(src), F::as_f32s_mut(dst)) {
+        simd::i16_to_f32(src, dst, gain as f32);
+        return Ok(());
+    }
+    for (x, y) in src.iter().zip(dst.iter_mut()) {
+        *y = F::from_f64(x.to_i32() as f64 * gain);
+    }
+    Ok(())
+}
+
+// `fullscale` maps to `format.max()`. Values round to nearest (ties to even) and saturate at the format's limits;
+// NaN becomes zero. Returns how many values were clipped.
+pub fn float_to_int<F: Float, I: Int>(src:&[F], dst:&mut [I], format:IntFormat, fullscale:f64) -> Result<usize, &'static str> {
+    check_lens(src.len(), dst.len())?;
+    check_container::<I>(format)?;
+    let gain = format.max() as f64 / fullscale;
+    let (min, max) = (format.min() as f64, format.max() as f64);
+    if let (Some(src), Some(dst)) = (F::as_f32s(src), I::as_i16s_mut(dst)) {
+        return Ok(simd::f32_to_i16(src, dst, gain as f32, min as f32, max as f32));
+    }
+    let mut clipped = 0;
+    for (x, y) in src.iter().zip(dst.iter_mut()) {
+        let v = x.to_f64() * gain;
+        if v.is_nan() {
+            *y = I::from_i32(0);
+            continue;
+        }
+        if v < min || v > max {
+            clipped += 1;
+        }
+        *y = I::from_i32(v.clamp(min, max).round_ties_even() as i32);
+    }
+    Ok(clipped)
+}
+
+// Lines up the top bits, the way UHD moves between wire and host formats: sc8 to sc16 multiplies by 256 and sc16
+// to sc12 rounds away the bottom 4 bits. Values outside either format's range saturate. Returns how many values
+// were clipped.
+pub fn int_to_int<I: Int, J: Int>(src:&[I], src_format:IntFormat, dst:&mut [J], dst_format:IntFormat) -> Result<usize, &'static str> {
+    check_lens(src.len(), dst.len())?;
+    check_container::<I>(src_format)?;
+    check_container::<J>(dst_format)?;
+    let (min, max) = (dst_format.min(), dst_format.max());
+    let mut clipped = 0;
+    for (x, y) in src.iter().zip(dst.iter_mut()) {
+        let raw = x.to_i32();
+        let x = raw.clamp(src_format.min(), src_format.max());
+        let v = if dst_format.bits() >= src_format.bits() {
+            x << (dst_format.bits() - src_format.bits())
+        } else {
+            let shift = src_format.bits() - dst_format.bits();
+            (x + (1 << (shift - 1))) >> shift
+        };
+        if v < min || v > max || x != raw {
+            clipped += 1;
+        }
+        *y = J::from_i32(v.clamp(min, max));
+    }
+    Ok(clipped)
+}
+
+pub fn float_to_float<F: Float, G: Float>(src:&[F], dst:&mut [G], gain:f64) -> Result<(), &'static str> {
+    check_lens(src.len(), dst.len())?;
+    for (x, y) in src.iter().zip(dst.iter_mut()) {
+        *y = G::from_f64(x.to_f64() * gain);
+    }
+    Ok(())
+}
+
+// Per-sample versions of the sc16 <-> fc32 conversions with a fullscale of 1.0
+pub fn sc16_to_fc32(x:i16) -> f32 {
+    x as f32 * simd::SC16_GAIN
+}
+
+pub fn fc32_to_sc16(x:f32) -> i16 {
+    simd::f32_to_i16_one(x, i16::MAX as f32, i16::MIN as f32, i16::MAX as f32).0
+}
@@ -0,0 +1,85 @@
+// Kernels for sc16 <-> fc32, the conversions that sit in streaming paths. On x86_64 they use SSE2, which every
+// x86_64 CPU has; elsewhere the scalar loops are left to the compiler's auto-vectorizer. Both give identical results.
+
+pub(crate) const SC16_GAIN:f32 = (1.0 / i16::MAX as f64) as f32;
+
+pub(crate) fn i16_to_f32(src:&[i16], dst:&mut [f32], gain:f32) {
+    #[cfg(target_arch = "x86_64")]
+    let done = unsafe { x86::i16_to_f32(src, dst, gain) };
+    #[cfg(not(target_arch = "x86_64"))]
+    let done = 0;
+
+    for (x, y) in src[done..].iter().zip(dst[done..].iter_mut()) {
+        *y = *x as f32 * gain;
+    }
+}
+
+// Returns the converted value and whether it was clipped
+pub(crate) fn f32_to_i16_one(x:f32, gain:f32, min:f32, max:f32) -> (i16, bool) {
+    let v = x * gain;
+    if v.is_nan() {
+        return (0, false);
+    }
+    (v.clamp(min, max).round_ties_even() as i16, v < min || v > max)
+}
+
+// Returns how many values were clipped
+pub(crate) fn f32_to_i16(src:&[f32], dst:&mut [i16], gain:f32, min:f32, max:f32) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    let (done, mut clipped) = unsafe { x86::f32_to_i16(src, dst, gain, min, max) };
+    #[cfg(not(target_arch = "x86_64"))]
+    let (done, mut clipped) = (0, 0);
+
+    for (x, y) in src[done..].iter().zip(dst[done..].iter_mut()) {
+        let (v, clip) = f32_to_i16_one(*x, gain, min, max);
+        *y = v;
+        clipped += clip as usize;
+    }
+    clipped
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    // Both return how many values they converted, a multiple of 8; the caller finishes the rest
+
+    pub(super) unsafe fn i16_to_f32(src:&[i16], dst:&mut [f32], gain:f32) -> usize {
+        let n = src.len().min(dst.len()) / 8 * 8;
+        let g = _mm_set1_ps(gain);
+        let mut idx = 0;
+        while idx < n {
+            let x = _mm_loadu_si128(src.as_ptr().add(idx) as *const __m128i);
+            // Each i16 lands in the top half of an i32 and an arithmetic shift brings it down sign-extended
+            let lo = _mm_srai_epi32(_mm_unpacklo_epi16(x, x), 16);
+            let hi = _mm_srai_epi32(_mm_unpackhi_epi16(x, x), 16);
+            _mm_storeu_ps(dst.as_mut_ptr().add(idx), _mm_mul_ps(_mm_cvtepi32_ps(lo), g));
+            _mm_storeu_ps(dst.as_mut_ptr().add(idx + 4), _mm_mul_ps(_mm_cvtepi32_ps(hi), g));
+            idx += 8;
+        }
+        n
+    }
+
+    // `_mm_cvtps_epi32` rounds ties to even under the default MXCSR, matching `round_ties_even`
+    pub(super) unsafe fn f32_to_i16(src:&[f32], dst:&mut [i16], gain:f32, min:f32, max:f32) -> (usize, usize) {
+        let n = src.len().min(dst.len()) / 8 * 8;
+        let (g, lo, hi) = (_mm_set1_ps(gain), _mm_set1_ps(min), _mm_set1_ps(max));
+        let mut clipped = 0;
+        let mut idx = 0;
+        while idx < n {
+            let mut out = [_mm_setzero_si128(); 2];
+            for (k, o) in out.iter_mut().enumerate() {
+                let v = _mm_mul_ps(_mm_loadu_ps(src.as_ptr().add(idx + 4 * k)), g);
+                // NaN compares false both ways, so it's zeroed here and never counted as clipped
+                let v = _mm_and_ps(v, _mm_cmpord_ps(v, v));
+                let out_of_range = _mm_or_ps(_mm_cmplt_ps(v, lo), _mm_cmpgt_ps(v, hi));
+                clipped += _mm_movemask_ps(out_of_range).count_ones() as usize;
+                *o = _mm_cvtps_epi32(_mm_min_ps(_mm_max_ps(v, lo), hi));
+            }
+            _mm_storeu_si128(dst.as_mut_ptr().add(idx) as *mut __m128i, _mm_packs_epi32(out[0], out[1]));
+            idx += 8;
+        }
+        (n, clipped)
+    }
+
+}
@@ -0,0 +1,103 @@
+use crate::convert::{self, Endian, IntFormat};
+
+#[test]
+fn byte_views() {
+    let iq:Vec<(i16, i16)> = vec![(1, -2), (0x1234, i16::MIN)];
+    let bytes = convert::iq_bytes(&iq).unwrap();
+    assert_eq!(8, bytes.len());
+    assert_eq!(&iq[..], convert::cast_iq::<i16>(bytes).unwrap());
+    assert_eq!(&[1, -2, 0x1234, i16::MIN], convert::flatten(&iq).unwrap());
+
+    // Wrong length, misaligned, odd number of components
+    assert!(convert::cast_slice::<i16>(&bytes[..7]).is_err());
+    assert!(convert::cast_slice::<i16>(&bytes[1..7]).is_err());
+    assert!(convert::pairs(&[1i16, 2, 3]).is_err());
+
+    let mut values = [0u32; 2];
+    convert::bytes_of_mut(&mut values).copy_from_slice(&[0xff; 8]);
+    assert_eq!([u32::MAX; 2], values);
+}
+
+#[test]
+fn byte_order() {
+    let iq = [(0x0102i16, -2i16)];
+    let mut le = [0u8; 4];
+    let mut be = [0u8; 4];
+    convert::encode_iq(&iq, &mut le, Endian::Little).unwrap();
+    convert::encode_iq(&iq, &mut be, Endian::Big).unwrap();
+    assert_eq!([0x02, 0x01, 0xfe, 0xff], le);
+    assert_eq!([0x01, 0x02, 0xff, 0xfe], be);
+    assert_eq!(&le[..], &convert::iq_to_le_bytes(&iq)[..]);
+
+    let mut back = [(0i16, 0i16)];
+    convert::decode_iq(&be, &mut back, Endian::Big).unwrap();
+    assert_eq!(iq, back);
+    assert_eq!(&iq[..], &convert::iq_from_le_bytes::<i16>(&le).unwrap()[..]);
+    assert!(convert::encode_iq(&iq, &mut [0u8; 3], Endian::Little).is_err());
+
+    let mut f = [0.0f64];
+    convert::decode(&1.5f64.to_be_bytes(), &mut f, Endian::Big).unwrap();
+    assert_eq!([1.5], f);
+}
+
+#[test]
+fn sc16_fc32_round_trip() {
+    // Odd length so both the SIMD body and the scalar tail run
+    let all:Vec<i16> = (i16::MIN..=i16::MAX).collect();
+    let mut floats = vec![0.0f32; all.len()];
+    convert::int_to_float(&all, IntFormat::Sc16, &mut floats, 1.0).unwrap();
+    for (x, y) in all.iter().zip(floats.iter()) {
+        assert_eq!(convert::sc16_to_fc32(*x), *y);
+    }
+
+    let mut back = vec![0i16; all.len()];
+    assert_eq!(0, convert::float_to_int(&floats, &mut back, IntFormat::Sc16, 1.0).unwrap());
+    assert_eq!(all, back);
+}
+
+#[test]
+fn saturation() {
+    let src = [2.0f32, -2.0, f32::NAN, 0.5, -0.5, 0.75, -1.0, 1.0, 0.25, f32::INFINITY, 0.0];
+    let mut simd = [0i16; 11];
+    assert_eq!(3, convert::float_to_int(&src, &mut simd, IntFormat::Sc16, 1.0).unwrap());
+    assert_eq!([32767, -32768, 0, 16384, -16384, 24575, -32767, 32767, 8192, 32767, 0], simd);
+    for (x, y) in src.iter().zip(simd.iter()) {
+        assert_eq!(convert::fc32_to_sc16(*x), *y);
+    }
+
+    // Same values through the generic path
+    let src64:Vec<f64> = src.iter().map(|x| *x as f64).collect();
+    let mut generic = [0i16; 11];
+    assert_eq!(3, convert::float_to_int(&src64, &mut generic, IntFormat::Sc16, 1.0).unwrap());
+    assert_eq!(simd, generic);
+}
+
+#[test]
+fn narrow_formats() {
+    let mut sc12 = [0i16; 4];
+    assert_eq!(2, convert::float_to_int(&[1.0f32, -1.5, 0.5, 100.0], &mut sc12, IntFormat::Sc12, 1.0).unwrap());
+    assert_eq!([2047, -2048, 1024, 2047], sc12);
+
+    let mut sc8 = [0i8; 3];
+    assert_eq!(1, convert::float_to_int(&[1.0f64, -0.5, -3.0], &mut sc8, IntFormat::Sc8, 1.0).unwrap());
+    assert_eq!([127, -64, -128], sc8);
+    assert!(convert::float_to_int(&[0.0f32], &mut [0i8], IntFormat::Sc16, 1.0).is_err());
+
+    let mut f = [0.0f64; 3];
+    convert::int_to_float(&sc8, IntFormat::Sc8, &mut f, 127.0).unwrap();
+    assert_eq!([127.0, -64.0, -128.0], f);
+
+    // Widening shifts up; narrowing rounds and saturates
+    let mut sc16 = [0i16; 3];
+    assert_eq!(0, convert::int_to_int(&sc8, IntFormat::Sc8, &mut sc16, IntFormat::Sc16).unwrap());
+    assert_eq!([127 * 256, -64 * 256, -128 * 256], sc16);
+    let mut narrowed = [0i16; 4];
+    assert_eq!(1, convert::int_to_int(&[32767i16, -32768, 8, 7], IntFormat::Sc16, &mut narrowed, IntFormat::Sc12).unwrap());
+    assert_eq!([2047, -2048, 1, 0], narrowed);
+    assert_eq!(1, convert::int_to_int(&[4000i16], IntFormat::Sc12, &mut narrowed[..1], IntFormat::Sc16).unwrap());
+    assert_eq!(2047 * 16, narrowed[0]);
+
+    let mut f32s = [0.0f32; 2];
+    convert::float_to_float(&[0.5f64, -1.0], &mut f32s, 2.0).unwrap();
+    assert_eq!([1.0, -2.0], f32s);
+}
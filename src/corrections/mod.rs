@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use crate::usrp::USRP;
+
+#[cfg(test)]
+mod tests;
+
+// Complex correction values are (real, imag) pairs normalized to full scale, the same convention UHD uses for
+// its own DC offset and IQ balance properties.  The C API only exposes the automatic enable/disable switches,
+// so explicit values are applied to the sample buffers on the host instead.
+pub type Correction = (f64, f64);
+
+const FULL_SCALE:f64 = 32767.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChannelCorrections {
+    pub agc:Option<bool>,               // None means this hasn't been set through this API yet
+    pub dc_offset_auto:Option<bool>,
+    pub iq_balance_auto:Option<bool>,
+    pub dc_offset:Correction,
+    pub iq_balance:Correction,
+}
+
+impl ChannelCorrections {
+
+    pub fn is_identity(&self) -> bool {
+        self.dc_offset == (0.0, 0.0) && self.iq_balance == (0.0, 0.0)
+    }
+
+    // Remove the DC offset and then apply the IQ balance correction y = x + c*conj(x)
+    pub fn correct_rx(&self, buff:&mut [(i16, i16)]) {
+        if self.is_identity() { return; }
+        let (dc_i, dc_q) = (self.dc_offset.0 * FULL_SCALE, self.dc_offset.1 * FULL_SCALE);
+        for sample in buff.iter_mut() {
+            let x = (sample.0 as f64 - dc_i, sample.1 as f64 - dc_q);
+            *sample = saturate(iq_balance(x, self.iq_balance));
+        }
+    }
+
+    // Apply the IQ balance correction and then add the DC offset, which is the inverse order of `correct_rx`
+    pub fn correct_tx(&self, buff:&mut [(i16, i16)]) {
+        if self.is_identity() { return; }
+        let (dc_i, dc_q) = (self.dc_offset.0 * FULL_SCALE, self.dc_offset.1 * FULL_SCALE);
+        for sample in buff.iter_mut() {
+            let (i, q) = iq_balance((sample.0 as f64, sample.1 as f64), self.iq_balance);
+            *sample = saturate((i + dc_i, q + dc_q));
+        }
+    }
+
+    // What to re-issue to get from `self` back to `snapshot`. A switch first set after the snapshot was taken is
+    // recorded as off, since that's the state this API assumes before anything has been set.
+    fn restore_target(&self, snapshot:&ChannelCorrections) -> ChannelCorrections {
+        let back = |then:Option<bool>, now:Option<bool>| then.or(now.map(|_| false));
+        ChannelCorrections {
+            agc: back(snapshot.agc, self.agc),
+            dc_offset_auto: back(snapshot.dc_offset_auto, self.dc_offset_auto),
+            iq_balance_auto: back(snapshot.iq_balance_auto, self.iq_balance_auto),
+            ..*snapshot
+        }
+    }
+
+    fn apply_rx(&self, usrp:&mut USRP, chan:usize) -> Result<(), &'static str> {
+        if let Some(en) = self.agc { usrp.set_rx_agc(en, chan)?; }
+        if let Some(en) = self.dc_offset_auto { usrp.set_rx_dc_offset_enabled(en, chan)?; }
+        if let Some(en) = self.iq_balance_auto { usrp.set_rx_iq_balance_enabled(en, chan)?; }
+        Ok(())
+    }
+
+}
+
+fn iq_balance(x:(f64, f64), c:Correction) -> (f64, f64) {
+    (x.0 + c.0*x.0 + c.1*x.1, x.1 + c.1*x.0 - c.0*x.1)
+}
+
+fn saturate(x:(f64, f64)) -> (i16, i16) {
+    let clamp = |v:f64| v.round().max(i16::MIN as f64).min(i16::MAX as f64) as i16;
+    (clamp(x.0), clamp(x.1))
+}
+
+// Tracks the correction state of every channel.  UHD has no getters for the automatic corrections, so the state
+// recorded here is what makes it possible to snapshot a channel and restore it later.
+#[derive(Clone, Debug, Default)]
+pub struct Corrections {
+    rx:BTreeMap<usize, ChannelCorrections>,
+    tx:BTreeMap<usize, ChannelCorrections>,
+}
+
+impl Corrections {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rx(&self, chan:usize) -> ChannelCorrections {
+        self.rx.get(&chan).copied().unwrap_or_default()
+    }
+
+    pub fn tx(&self, chan:usize) -> ChannelCorrections {
+        self.tx.get(&chan).copied().unwrap_or_default()
+    }
+
+    pub fn set_rx_agc(&mut self, usrp:&mut USRP, enable:bool, chan:usize) -> Result<(), &'static str> {
+        usrp.set_rx_agc(enable, chan)?;
+        self.rx.entry(chan).or_default().agc = Some(enable);
+        Ok(())
+    }
+
+    pub fn set_rx_dc_offset_enabled(&mut self, usrp:&mut USRP, enable:bool, chan:usize) -> Result<(), &'static str> {
+        usrp.set_rx_dc_offset_enabled(enable, chan)?;
+        self.rx.entry(chan).or_default().dc_offset_auto = Some(enable);
+        Ok(())
+    }
+
+    pub fn set_rx_iq_balance_enabled(&mut self, usrp:&mut USRP, enable:bool, chan:usize) -> Result<(), &'static str> {
+        usrp.set_rx_iq_balance_enabled(enable, chan)?;
+        self.rx.entry(chan).or_default().iq_balance_auto = Some(enable);
+        Ok(())
+    }
+
+    pub fn set_rx_dc_offset(&mut self, offset:Correction, chan:usize) {
+        self.rx.entry(chan).or_default().dc_offset = offset;
+    }
+
+    pub fn set_rx_iq_balance(&mut self, correction:Correction, chan:usize) {
+        self.rx.entry(chan).or_default().iq_balance = correction;
+    }
+
+    pub fn set_tx_dc_offset(&mut self, offset:Correction, chan:usize) {
+        self.tx.entry(chan).or_default().dc_offset = offset;
+    }
+
+    pub fn set_tx_iq_balance(&mut self, correction:Correction, chan:usize) {
+        self.tx.entry(chan).or_default().iq_balance = correction;
+    }
+
+    pub fn correct_rx(&self, buff:&mut [(i16, i16)], chan:usize) {
+        if let Some(c) = self.rx.get(&chan) { c.correct_rx(buff); }
+    }
+
+    pub fn correct_tx(&self, buff:&mut [(i16, i16)], chan:usize) {
+        if let Some(c) = self.tx.get(&chan) { c.correct_tx(buff); }
+    }
+
+    pub fn snapshot(&self, chan:usize) -> (ChannelCorrections, ChannelCorrections) {
+        (self.rx(chan), self.tx(chan))
+    }
+
+    // Puts a channel back into the state captured by `snapshot`, re-issuing any automatic settings to the device
+    pub fn restore(&mut self, usrp:&mut USRP, chan:usize, snapshot:&(ChannelCorrections, ChannelCorrections)) -> Result<(), &'static str> {
+        let (rx, tx) = snapshot;
+        let rx = self.rx(chan).restore_target(rx);
+        rx.apply_rx(usrp, chan)?;
+        self.rx.insert(chan, rx);
+        self.tx.insert(chan, *tx);
+        Ok(())
+    }
+
+}
@@ -0,0 +1,57 @@
+use crate::corrections::{ChannelCorrections, Corrections};
+
+#[test]
+fn dc_offset_round_trip() {
+    let c = ChannelCorrections{ dc_offset:(0.25, -0.125), ..Default::default() };
+
+    let mut buff = vec![(1000, -2000), (0, 0)];
+    c.correct_tx(&mut buff);
+    assert_eq!(buff, vec![(9192, -6096), (8192, -4096)]);
+
+    c.correct_rx(&mut buff);
+    assert_eq!(buff, vec![(1000, -2000), (0, 0)]);
+}
+
+#[test]
+fn iq_balance_saturates() {
+    let c = ChannelCorrections{ iq_balance:(0.5, 0.0), ..Default::default() };
+    let mut buff = vec![(30000, 1000)];
+    c.correct_rx(&mut buff);
+    assert_eq!(buff, vec![(32767, 500)]);
+}
+
+#[test]
+fn untouched_channels_pass_through() {
+    let mut corr = Corrections::new();
+    corr.set_rx_dc_offset((0.1, 0.1), 1);
+
+    let mut buff = vec![(123, 456)];
+    corr.correct_rx(&mut buff, 0);
+    corr.correct_tx(&mut buff, 1);
+    assert_eq!(buff, vec![(123, 456)]);
+}
+
+#[test]
+fn snapshot_captures_manual_values() {
+    let mut corr = Corrections::new();
+    corr.set_rx_iq_balance((0.01, -0.02), 2);
+    corr.set_tx_dc_offset((0.03, 0.0), 2);
+
+    let (rx, tx) = corr.snapshot(2);
+    assert_eq!(rx.iq_balance, (0.01, -0.02));
+    assert_eq!(tx.dc_offset, (0.03, 0.0));
+    assert_eq!(rx.agc, None);
+    assert!(corr.snapshot(0).0.is_identity());
+}
+
+#[test]
+fn restore_turns_off_later_switches() {
+    let snapshot = ChannelCorrections{ dc_offset_auto: Some(true), ..Default::default() };
+    let now = ChannelCorrections{ agc: Some(true), dc_offset_auto: Some(false), iq_balance: (0.1, 0.0), ..Default::default() };
+
+    let target = now.restore_target(&snapshot);
+    assert_eq!(Some(false), target.agc);
+    assert_eq!(Some(true), target.dc_offset_auto);
+    assert_eq!(None, target.iq_balance_auto);
+    assert_eq!((0.0, 0.0), target.iq_balance);
+}
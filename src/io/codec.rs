@@ -0,0 +1,70 @@
+use crate::convert::{self, Endian, IntFormat, Sample};
+
+// Moves I/Q between the host and the interleaved little-endian sc16 or fc32 bytes that raw, WAV and .npy files
+// hold, with a full scale of 1.0 for floats.  The working buffers are kept between calls so streaming a file
+// doesn't allocate per chunk.
+#[derive(Default)]
+pub(crate) struct IqCodec {
+    bytes:Vec<u8>,
+    sc16:Vec<(i16, i16)>,
+    fc32:Vec<(f32, f32)>,
+}
+
+impl IqCodec {
+
+    pub(crate) fn sample_size(float:bool) -> usize {
+        if float { 8 } else { 4 }
+    }
+
+    pub(crate) fn encode_sc16(&mut self, samples:&[(i16, i16)], float:bool) -> Result<&[u8], &'static str> {
+        if !float {
+            return encode(samples, &mut self.bytes);
+        }
+        self.fc32.resize(samples.len(), (0.0, 0.0));
+        convert::int_to_float(convert::flatten(samples)?, IntFormat::Sc16, convert::flatten_mut(&mut self.fc32)?, 1.0)?;
+        encode(&self.fc32, &mut self.bytes)
+    }
+
+    pub(crate) fn encode_fc32(&mut self, samples:&[(f32, f32)], float:bool) -> Result<&[u8], &'static str> {
+        if float {
+            return encode(samples, &mut self.bytes);
+        }
+        self.sc16.resize(samples.len(), (0, 0));
+        convert::float_to_int(convert::flatten(samples)?, convert::flatten_mut(&mut self.sc16)?, IntFormat::Sc16, 1.0)?;
+        encode(&self.sc16, &mut self.bytes)
+    }
+
+    // Room for `samples` samples to be read into before decoding them
+    pub(crate) fn bytes_mut(&mut self, samples:usize, float:bool) -> &mut [u8] {
+        self.bytes.resize(samples * Self::sample_size(float), 0);
+        &mut self.bytes
+    }
+
+    // Decodes as many samples from the start of the buffer as `dst` holds
+    pub(crate) fn decode_sc16(&mut self, dst:&mut [(i16, i16)], float:bool) -> Result<(), &'static str> {
+        let bytes = self.bytes.get(..dst.len() * Self::sample_size(float)).ok_or("Not enough bytes to decode")?;
+        if !float {
+            return convert::decode_iq(bytes, dst, Endian::Little);
+        }
+        self.fc32.resize(dst.len(), (0.0, 0.0));
+        convert::decode_iq(bytes, &mut self.fc32, Endian::Little)?;
+        convert::float_to_int(convert::flatten(&self.fc32)?, convert::flatten_mut(dst)?, IntFormat::Sc16, 1.0).map(|_| ())
+    }
+
+    pub(crate) fn decode_fc32(&mut self, dst:&mut [(f32, f32)], float:bool) -> Result<(), &'static str> {
+        let bytes = self.bytes.get(..dst.len() * Self::sample_size(float)).ok_or("Not enough bytes to decode")?;
+        if float {
+            return convert::decode_iq(bytes, dst, Endian::Little);
+        }
+        self.sc16.resize(dst.len(), (0, 0));
+        convert::decode_iq(bytes, &mut self.sc16, Endian::Little)?;
+        convert::int_to_float(convert::flatten(&self.sc16)?, IntFormat::Sc16, convert::flatten_mut(dst)?, 1.0)
+    }
+
+}
+
+fn encode<'a, T: Sample>(samples:&[(T, T)], bytes:&'a mut Vec<u8>) -> Result<&'a [u8], &'static str> {
+    bytes.resize(samples.len() * 2 * T::SIZE, 0);
+    convert::encode_iq(samples, bytes, Endian::Little)?;
+    Ok(bytes)
+}
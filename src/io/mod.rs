@@ -1,12 +1,18 @@
 use std::path::Path;
 
-pub fn write_sc16_to_file<P: AsRef<Path>>(path:P, data:&[(i16, i16)]) -> Result<(), &'static str> {
+mod codec;
+
+pub mod npy;
+pub mod sigmf;
+pub mod transcode;
+pub mod wav;
 
-    let data_u8: &[u8] = unsafe {
-        let data_ptr: *const u8 = data.as_ptr() as *const u8;
-        std::slice::from_raw_parts(data_ptr, data.len() * std::mem::size_of::<(i16, i16)>())
-    };
+use crate::convert;
 
-    std::fs::write(path, data_u8).map_err(|_| "Unable to write &[(i16, i16)] to a file")
+// Full scale is 32767 both ways so sc16 survives a round trip through fc32 exactly
+pub use crate::convert::{fc32_to_sc16, sc16_to_fc32};
 
-}
\ No newline at end of file
+// Interleaved little-endian I/Q whatever the host's byte order
+pub fn write_sc16_to_file<P: AsRef<Path>>(path:P, data:&[(i16, i16)]) -> Result<(), &'static str> {
+    std::fs::write(path, convert::iq_to_le_bytes(data)).map_err(|_| "Unable to write &[(i16, i16)] to a file")
+}
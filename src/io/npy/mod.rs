@@ -0,0 +1,180 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::io::codec::IqCodec;
+
+#[cfg(test)]
+mod tests;
+
+const MAGIC:&[u8] = b"\x93NUMPY";
+
+// Room for any shape we could write, so the header can be rewritten in place once the length is known
+const HEADER_LEN:usize = 128;
+
+// `Complex64` loads in NumPy as complex samples; `Int16Pairs` keeps sc16 exactly as an (n, 2) int16 array
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NpyDtype { Complex64, Int16Pairs }
+
+impl NpyDtype {
+
+    fn descr(&self) -> &'static str {
+        match self {
+            NpyDtype::Complex64 => "<c8",
+            NpyDtype::Int16Pairs => "<i2",
+        }
+    }
+
+    fn is_float(&self) -> bool { *self == NpyDtype::Complex64 }
+
+    fn shape(&self, n:u64) -> String {
+        match self {
+            NpyDtype::Complex64 => format!("({},)", n),
+            NpyDtype::Int16Pairs => format!("({}, 2)", n),
+        }
+    }
+
+}
+
+pub struct NpyWriter {
+    file:BufWriter<File>,
+    dtype:NpyDtype,
+    samples:u64,
+    codec:IqCodec,
+}
+
+pub struct NpyReader {
+    file:BufReader<File>,
+    dtype:NpyDtype,
+    samples:u64,
+    samples_read:u64,
+    codec:IqCodec,
+}
+
+fn header(dtype:NpyDtype, samples:u64) -> Vec<u8> {
+    let dict = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", dtype.descr(), dtype.shape(samples));
+    let mut ans = MAGIC.to_vec();
+    ans.extend_from_slice(&[1, 0]);
+    ans.extend_from_slice(&((HEADER_LEN - 10) as u16).to_le_bytes());
+    ans.extend_from_slice(dict.as_bytes());
+    ans.resize(HEADER_LEN - 1, b' ');
+    ans.push(b'\n');
+    ans
+}
+
+impl NpyWriter {
+
+    pub fn create<P: AsRef<Path>>(path:P, dtype:NpyDtype) -> Result<Self, &'static str> {
+        let mut file = BufWriter::new(File::create(path).map_err(|_| "Unable to create .npy file")?);
+        file.write_all(&header(dtype, 0)).map_err(|_| "Unable to write .npy header")?;
+        Ok(Self{ file, dtype, samples: 0, codec: IqCodec::default() })
+    }
+
+    pub fn samples(&self) -> u64 { self.samples }
+
+    pub fn write_sc16(&mut self, samples:&[(i16, i16)]) -> Result<(), &'static str> {
+        let bytes = self.codec.encode_sc16(samples, self.dtype.is_float())?;
+        self.file.write_all(bytes).map_err(|_| "Unable to write to .npy file")?;
+        self.samples += samples.len() as u64;
+        Ok(())
+    }
+
+    pub fn write_fc32(&mut self, samples:&[(f32, f32)]) -> Result<(), &'static str> {
+        let bytes = self.codec.encode_fc32(samples, self.dtype.is_float())?;
+        self.file.write_all(bytes).map_err(|_| "Unable to write to .npy file")?;
+        self.samples += samples.len() as u64;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), &'static str> {
+        self.file.flush().map_err(|_| "Unable to flush .npy file")?;
+        let file = self.file.get_mut();
+        file.seek(SeekFrom::Start(0)).map_err(|_| "Unable to seek in .npy file")?;
+        file.write_all(&header(self.dtype, self.samples)).map_err(|_| "Unable to write .npy header")
+    }
+
+}
+
+impl NpyReader {
+
+    pub fn open<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let mut file = BufReader::new(File::open(path).map_err(|_| "Unable to open .npy file")?);
+        let mut preamble = [0u8; 8];
+        file.read_exact(&mut preamble).map_err(|_| ".npy file is too short")?;
+        if &preamble[..6] != MAGIC {
+            return Err("Not a .npy file");
+        }
+
+        // Version 1 has a 2-byte header length; versions 2 and 3 use 4 bytes
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                file.read_exact(&mut len).map_err(|_| ".npy file is too short")?;
+                u16::from_le_bytes(len) as usize
+            },
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                file.read_exact(&mut len).map_err(|_| ".npy file is too short")?;
+                u32::from_le_bytes(len) as usize
+            },
+            _ => return Err("Unsupported .npy version"),
+        };
+        let mut dict = vec![0u8; header_len];
+        file.read_exact(&mut dict).map_err(|_| ".npy header is too short")?;
+        let dict = String::from_utf8(dict).map_err(|_| ".npy header isn't valid text")?;
+
+        let (dtype, samples) = parse_header(&dict)?;
+        Ok(Self{ file, dtype, samples, samples_read: 0, codec: IqCodec::default() })
+    }
+
+    pub fn dtype(&self) -> NpyDtype { self.dtype }
+    pub fn samples(&self) -> u64 { self.samples }
+
+    // Reads up to `max` samples into the codec's buffer
+    fn read_samples(&mut self, max:usize) -> Result<usize, &'static str> {
+        let n = (self.samples - self.samples_read).min(max as u64) as usize;
+        let bytes = self.codec.bytes_mut(n, self.dtype.is_float());
+        self.file.read_exact(bytes).map_err(|_| ".npy file is shorter than its header says")?;
+        self.samples_read += n as u64;
+        Ok(n)
+    }
+
+    // Returns the number of samples read, which is zero at the end of the file
+    pub fn read_sc16(&mut self, buff:&mut [(i16, i16)]) -> Result<usize, &'static str> {
+        let n = self.read_samples(buff.len())?;
+        self.codec.decode_sc16(&mut buff[..n], self.dtype.is_float())?;
+        Ok(n)
+    }
+
+    pub fn read_fc32(&mut self, buff:&mut [(f32, f32)]) -> Result<usize, &'static str> {
+        let n = self.read_samples(buff.len())?;
+        self.codec.decode_fc32(&mut buff[..n], self.dtype.is_float())?;
+        Ok(n)
+    }
+
+}
+
+// Pulls dtype and length out of a header like {'descr': '<c8', 'fortran_order': False, 'shape': (1000,), }
+pub(crate) fn parse_header(dict:&str) -> Result<(NpyDtype, u64), &'static str> {
+    let value = |key:&str| -> Result<&str, &'static str> {
+        let start = dict.find(&format!("'{}':", key)).ok_or(".npy header is missing a field")? + key.len() + 3;
+        Ok(dict[start..].trim_start())
+    };
+
+    let descr = value("descr")?;
+    let descr = descr.get(1..).and_then(|d| d.split('\'').next()).ok_or(".npy header has a bad descr")?;
+    if value("fortran_order")?.starts_with("True") {
+        return Err("Fortran-ordered .npy arrays aren't supported");
+    }
+    let shape = value("shape")?;
+    let shape = shape.get(1..shape.find(')').ok_or(".npy header has a bad shape")?).ok_or(".npy header has a bad shape")?;
+    let dims = shape.split(',').map(|d| d.trim()).filter(|d| !d.is_empty())
+        .map(|d| d.parse::<u64>().map_err(|_| ".npy header has a bad shape"))
+        .collect::<Result<Vec<u64>, &'static str>>()?;
+
+    match (descr, dims.as_slice()) {
+        ("<c8", [n]) => Ok((NpyDtype::Complex64, *n)),
+        ("<i2", [n, 2]) => Ok((NpyDtype::Int16Pairs, *n)),
+        _ => Err("Only complex64 vectors or (n, 2) int16 .npy arrays hold I/Q"),
+    }
+}
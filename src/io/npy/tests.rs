@@ -0,0 +1,34 @@
+use crate::io::npy::{self, NpyDtype, NpyReader, NpyWriter};
+use crate::test_util::TempDir;
+
+#[test]
+fn headers() {
+    assert_eq!(Ok((NpyDtype::Complex64, 1000)), npy::parse_header("{'descr': '<c8', 'fortran_order': False, 'shape': (1000,), }"));
+    assert_eq!(Ok((NpyDtype::Int16Pairs, 7)), npy::parse_header("{'shape': (7, 2), 'fortran_order': False, 'descr': '<i2'}"));
+    assert!(npy::parse_header("{'descr': '<c8', 'fortran_order': True, 'shape': (1000,), }").is_err());
+    assert!(npy::parse_header("{'descr': '<f8', 'fortran_order': False, 'shape': (1000,), }").is_err());
+
+    let header = npy::header(NpyDtype::Complex64, 12);
+    assert_eq!(0, header.len() % 64);
+    assert_eq!(b'\n', header[header.len() - 1]);
+}
+
+#[test]
+fn round_trip() {
+    let dir = TempDir::new("npy");
+    for dtype in [NpyDtype::Complex64, NpyDtype::Int16Pairs] {
+        let path = dir.join(format!("{:?}.npy", dtype));
+        let samples:Vec<(i16, i16)> = (0..500).map(|n| (n as i16 * 60, i16::MIN + n as i16)).collect();
+
+        let mut writer = NpyWriter::create(&path, dtype).unwrap();
+        writer.write_sc16(&samples).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = NpyReader::open(&path).unwrap();
+        assert_eq!(dtype, reader.dtype());
+        assert_eq!(500, reader.samples());
+        let mut buff = vec![(0, 0); 600];
+        assert_eq!(500, reader.read_sc16(&mut buff).unwrap());
+        assert_eq!(&samples[..], &buff[..500]);
+    }
+}
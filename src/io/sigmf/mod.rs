@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::types::TimeSpec;
+use crate::usrp::USRP;
+
+mod reader;
+mod writer;
+pub use reader::{CaptureSegment, Datatype, SigMfReader};
+pub use writer::SigMfWriter;
+
+#[cfg(test)]
+mod tests;
+
+pub const SIGMF_VERSION:&str = "1.0.0";
+
+// Fields we add outside the core namespace are declared as an optional extension so other readers can skip them
+pub const EXTENSION_NAME:&str = "uhd_rs";
+pub const EXTENSION_VERSION:&str = "0.1.0";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Extension {
+    pub name:String,
+    pub version:String,
+    pub optional:bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Global {
+    #[serde(rename = "core:datatype")]
+    pub datatype:String,
+    #[serde(rename = "core:version")]
+    pub version:String,
+    #[serde(rename = "core:sample_rate", default, skip_serializing_if = "Option::is_none")]
+    pub sample_rate:Option<f64>,
+    #[serde(rename = "core:num_channels", default, skip_serializing_if = "Option::is_none")]
+    pub num_channels:Option<usize>,
+    #[serde(rename = "core:hw", default, skip_serializing_if = "Option::is_none")]
+    pub hw:Option<String>,
+    #[serde(rename = "core:description", default, skip_serializing_if = "Option::is_none")]
+    pub description:Option<String>,
+    #[serde(rename = "core:author", default, skip_serializing_if = "Option::is_none")]
+    pub author:Option<String>,
+    #[serde(rename = "core:recorder", default, skip_serializing_if = "Option::is_none")]
+    pub recorder:Option<String>,
+    #[serde(rename = "core:extensions", default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions:Vec<Extension>,
+    #[serde(flatten)]
+    pub extra:Map<String, Value>,
+}
+
+// A capture segment starts at `sample_start` and runs until the next one; a new segment marks a discontinuity
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Capture {
+    #[serde(rename = "core:sample_start")]
+    pub sample_start:u64,
+    #[serde(rename = "core:frequency", default, skip_serializing_if = "Option::is_none")]
+    pub frequency:Option<f64>,
+    #[serde(rename = "core:datetime", default, skip_serializing_if = "Option::is_none")]
+    pub datetime:Option<String>,
+    #[serde(rename = "uhd_rs:time_spec", default, skip_serializing_if = "Option::is_none")]
+    pub time_spec:Option<TimeSpec>,
+    #[serde(rename = "uhd_rs:gain", default, skip_serializing_if = "Option::is_none")]
+    pub gain:Option<f64>,
+    #[serde(flatten)]
+    pub extra:Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(rename = "core:sample_start")]
+    pub sample_start:u64,
+    #[serde(rename = "core:sample_count", default, skip_serializing_if = "Option::is_none")]
+    pub sample_count:Option<u64>,
+    #[serde(rename = "core:label", default, skip_serializing_if = "Option::is_none")]
+    pub label:Option<String>,
+    #[serde(rename = "core:comment", default, skip_serializing_if = "Option::is_none")]
+    pub comment:Option<String>,
+    #[serde(flatten)]
+    pub extra:Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SigMfMeta {
+    pub global:Global,
+    #[serde(default)]
+    pub captures:Vec<Capture>,
+    #[serde(default)]
+    pub annotations:Vec<Annotation>,
+}
+
+impl Global {
+
+    // Metadata for complex sc16 samples as UHD produces them
+    pub fn ci16(sample_rate:f64, num_channels:usize) -> Self {
+        Self {
+            datatype: "ci16_le".to_owned(),
+            version: SIGMF_VERSION.to_owned(),
+            sample_rate: Some(sample_rate),
+            num_channels: Some(num_channels),
+            recorder: Some(format!("uhd_rs {}", env!("CARGO_PKG_VERSION"))),
+            extensions: vec![Extension{ name: EXTENSION_NAME.to_owned(), version: EXTENSION_VERSION.to_owned(), optional: true }],
+            ..Default::default()
+        }
+    }
+
+    // Metadata for complex float samples, which keep full precision and range
+    pub fn cf32(sample_rate:f64, num_channels:usize) -> Self {
+        Self { datatype: "cf32_le".to_owned(), ..Self::ci16(sample_rate, num_channels) }
+    }
+
+    // Sample rate and hardware description of RX channels as they're currently configured
+    pub fn for_rx(usrp:&USRP, chans:&[usize]) -> Result<Self, &'static str> {
+        let first = *chans.first().ok_or("Need at least one channel to describe")?;
+        let mut ans = Self::ci16(usrp.get_rx_rate(first)?, chans.len());
+        let mut hw = vec![];
+        for chan in chans {
+            hw.push(hw_description(usrp, *chan)?);
+        }
+        ans.hw = Some(hw.join("; "));
+        Ok(ans)
+    }
+
+}
+
+impl Capture {
+
+    pub fn new(frequency:f64, gain:f64) -> Self {
+        Self { frequency: Some(frequency), gain: Some(gain), ..Default::default() }
+    }
+
+    pub fn for_rx(usrp:&USRP, chan:usize) -> Result<Self, &'static str> {
+        Ok(Self::new(usrp.get_rx_freq(chan)?, usrp.get_rx_gain(chan, "")?))
+    }
+
+}
+
+impl Annotation {
+
+    pub fn new(sample_start:u64, sample_count:Option<u64>, label:&str, comment:&str) -> Self {
+        Self {
+            sample_start, sample_count,
+            label: Some(label.to_owned()),
+            comment: Some(comment.to_owned()),
+            ..Default::default()
+        }
+    }
+
+}
+
+impl SigMfMeta {
+
+    pub fn to_json(&self) -> Result<String, &'static str> {
+        serde_json::to_string_pretty(self).map_err(|_| "Unable to serialize SigMF metadata")
+    }
+
+    pub fn from_json(s:&str) -> Result<Self, &'static str> {
+        serde_json::from_str(s).map_err(|_| "Unable to parse SigMF metadata")
+    }
+
+}
+
+// Like "X310 30C8B2A / UBX-160 31A5B1C / RX2", from `get_rx_info`
+pub fn hw_description(usrp:&USRP, chan:usize) -> Result<String, &'static str> {
+    let info = usrp.get_rx_info(chan)?;
+    Ok(format!("{} {} / {} {} / {}", info.mboard_id()?, info.mboard_serial()?, info.subdev_name()?, info.serial()?, info.antenna()?))
+}
+
+// Both files of a recording from either file name or the base name without an extension
+pub fn paths<P: AsRef<Path>>(path:P) -> (PathBuf, PathBuf) {
+    let path = path.as_ref();
+    let base = match path.extension().and_then(|e| e.to_str()) {
+        Some("sigmf-data") | Some("sigmf-meta") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    };
+    let with_ext = |ext:&str| {
+        let mut name = base.clone().into_os_string();
+        name.push(ext);
+        PathBuf::from(name)
+    };
+    (with_ext(".sigmf-data"), with_ext(".sigmf-meta"))
+}
+
+// ISO 8601 UTC timestamp for a time spec counted from the Unix epoch, e.g. after syncing device time to GPS
+pub fn iso8601(time_spec:TimeSpec) -> String {
+    let micros = (time_spec.1 * 1e6).round() as i64;
+    let secs = time_spec.0 + micros.div_euclid(1_000_000);
+    let micros = micros.rem_euclid(1_000_000);
+
+    // Days to civil date, from Howard Hinnant's date algorithms
+    let days = secs.div_euclid(86400);
+    let tod = secs.rem_euclid(86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z", year, month, day, tod / 3600, (tod / 60) % 60, tod % 60, micros)
+}
+
+// Inverse of `iso8601`; accepts any number of fractional digits and requires a UTC ("Z") timestamp
+pub fn parse_iso8601(s:&str) -> Option<TimeSpec> {
+    let s = s.strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<i64>());
+    let (year, month, day) = (date_parts.next()?.ok()?, date_parts.next()?.ok()?, date_parts.next()?.ok()?);
+    let (hms, frac) = match time.split_once('.') {
+        Some((hms, frac)) => (hms, format!("0.{}", frac).parse::<f64>().ok()?),
+        None => (time, 0.0),
+    };
+    let mut time_parts = hms.splitn(3, ':').map(|p| p.parse::<i64>());
+    let (hour, minute, second) = (time_parts.next()?.ok()?, time_parts.next()?.ok()?, time_parts.next()?.ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Civil date to days, from the same algorithms as `iso8601`
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some((days * 86400 + hour * 3600 + minute * 60 + second, frac))
+}
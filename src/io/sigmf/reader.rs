@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::convert::{self, Endian, Pod};
+use crate::io::sigmf::{self, Annotation, Capture, SigMfMeta};
+use crate::timing::time_sync::{time_add, time_diff};
+use crate::types::TimeSpec;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Datatype { Ci16Le, Cf32Le, Ci8 }
+
+impl Datatype {
+
+    pub fn parse(s:&str) -> Result<Self, &'static str> {
+        match s {
+            "ci16_le" => Ok(Datatype::Ci16Le),
+            "cf32_le" => Ok(Datatype::Cf32Le),
+            "ci8" | "ci8_le" => Ok(Datatype::Ci8),
+            _ => Err("Unsupported SigMF datatype"),
+        }
+    }
+
+    // Bytes in one complex sample of one channel
+    pub fn sample_size(&self) -> usize {
+        match self {
+            Datatype::Ci16Le => 4,
+            Datatype::Cf32Le => 8,
+            Datatype::Ci8 => 2,
+        }
+    }
+
+}
+
+// A capture segment together with the range of sample indices it covers
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureSegment<'a> {
+    pub capture:&'a Capture,
+    pub samples:Range<u64>,
+}
+
+// Memory-maps the data file so samples can be handed out as slices without copying
+pub struct SigMfReader {
+    pub meta:SigMfMeta,
+    pub datatype:Datatype,
+    data_path:PathBuf,
+    data:Option<Mmap>,      // Empty files can't be mapped
+}
+
+impl SigMfReader {
+
+    pub fn open<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let (data_path, meta_path) = sigmf::paths(path);
+        let meta_str = std::fs::read_to_string(&meta_path).map_err(|_| "Unable to read SigMF metadata file")?;
+        let meta = SigMfMeta::from_json(&meta_str)?;
+        let datatype = Datatype::parse(&meta.global.datatype)?;
+
+        let file = File::open(&data_path).map_err(|_| "Unable to open SigMF data file")?;
+        let len = file.metadata().map_err(|_| "Unable to get SigMF data file size")?.len();
+        let data = match len {
+            0 => None,
+            _ => Some(unsafe { Mmap::map(&file) }.map_err(|_| "Unable to memory-map SigMF data file")?),
+        };
+
+        let ans = Self{ meta, datatype, data_path, data };
+        if ans.num_samples() as usize * datatype.sample_size() * ans.num_channels() != ans.bytes().len() {
+            return Err("SigMF data file doesn't hold a whole number of samples");
+        }
+        Ok(ans)
+    }
+
+    // Every recording in a directory, in file name order
+    pub fn open_dir<P: AsRef<Path>>(dir:P) -> Result<Vec<Self>, &'static str> {
+        let mut meta_paths:Vec<PathBuf> = std::fs::read_dir(dir).map_err(|_| "Unable to read directory")?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().map(|e| e == "sigmf-meta").unwrap_or(false))
+            .collect();
+        meta_paths.sort();
+        meta_paths.iter().map(Self::open).collect()
+    }
+
+    pub fn data_path(&self) -> &Path { &self.data_path }
+    pub fn sample_rate(&self) -> Option<f64> { self.meta.global.sample_rate }
+    pub fn num_channels(&self) -> usize { self.meta.global.num_channels.unwrap_or(1).max(1) }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.data.as_deref().unwrap_or(&[])
+    }
+
+    // Samples per channel
+    pub fn num_samples(&self) -> u64 {
+        (self.bytes().len() / (self.datatype.sample_size() * self.num_channels())) as u64
+    }
+
+    // Multi-byte sample types are only handed out in place on a little-endian host
+    fn typed<T: Pod>(&self, datatype:Datatype) -> Result<&[(T, T)], &'static str> {
+        if self.datatype != datatype {
+            return Err("SigMF recording has a different datatype");
+        }
+        if Endian::NATIVE == Endian::Big && datatype != Datatype::Ci8 {
+            return Err("Little-endian samples can't be viewed in place on a big-endian host");
+        }
+        convert::cast_iq(self.bytes())
+    }
+
+    // With multiple channels, samples are interleaved one per channel per time step
+    pub fn ci16(&self) -> Result<&[(i16, i16)], &'static str> { self.typed(Datatype::Ci16Le) }
+    pub fn cf32(&self) -> Result<&[(f32, f32)], &'static str> { self.typed(Datatype::Cf32Le) }
+    pub fn ci8(&self) -> Result<&[(i8, i8)], &'static str> { self.typed(Datatype::Ci8) }
+
+    pub fn captures(&self) -> impl Iterator<Item=CaptureSegment<'_>> {
+        let total = self.num_samples();
+        let captures = &self.meta.captures;
+        captures.iter().enumerate().map(move |(idx, capture)| {
+            let end = captures.get(idx + 1).map(|c| c.sample_start).unwrap_or(total);
+            CaptureSegment{ capture, samples: capture.sample_start..end.max(capture.sample_start) }
+        })
+    }
+
+    pub fn capture_at(&self, sample:u64) -> Option<CaptureSegment<'_>> {
+        self.captures().filter(|seg| seg.capture.sample_start <= sample).last()
+    }
+
+    pub fn annotations(&self) -> impl Iterator<Item=&Annotation> {
+        self.meta.annotations.iter()
+    }
+
+    // Annotations that start in or overlap the given range of samples
+    pub fn annotations_in(&self, samples:Range<u64>) -> impl Iterator<Item=&Annotation> {
+        self.meta.annotations.iter().filter(move |a| {
+            let end = a.sample_start + a.sample_count.unwrap_or(0).max(1);
+            a.sample_start < samples.end && end > samples.start
+        })
+    }
+
+    // Absolute time of a sample, from the start time of the capture segment it falls in
+    pub fn time_of_sample(&self, sample:u64) -> Option<TimeSpec> {
+        let seg = self.capture_at(sample)?;
+        let start = capture_time(seg.capture)?;
+        Some(time_add(start, (sample - seg.capture.sample_start) as f64 / self.sample_rate()?))
+    }
+
+    // Index of the sample nearest to a time, if that time falls inside a capture segment
+    pub fn sample_at_time(&self, time_spec:TimeSpec) -> Option<u64> {
+        let rate = self.sample_rate()?;
+        self.captures().find_map(|seg| {
+            let offset = (time_diff(time_spec, capture_time(seg.capture)?) * rate).round();
+            let sample = seg.capture.sample_start as f64 + offset;
+            match offset >= 0.0 && sample < seg.samples.end as f64 {
+                true => Some(sample as u64),
+                false => None,
+            }
+        })
+    }
+
+}
+
+// Our own time spec when it was recorded, otherwise the standard datetime field
+fn capture_time(capture:&Capture) -> Option<TimeSpec> {
+    capture.time_spec.or_else(|| capture.datetime.as_deref().and_then(sigmf::parse_iso8601))
+}
@@ -0,0 +1,86 @@
+use crate::io::sigmf::{self, Capture, Datatype, Global, SigMfMeta, SigMfReader, SigMfWriter};
+use crate::test_util::TempDir;
+
+#[test]
+fn file_names() {
+    let (data, meta) = sigmf::paths("/tmp/capture.sigmf-meta");
+    assert_eq!("/tmp/capture.sigmf-data", data.to_str().unwrap());
+    assert_eq!("/tmp/capture.sigmf-meta", meta.to_str().unwrap());
+
+    let (data, _) = sigmf::paths("/tmp/rx_2.4e9");
+    assert_eq!("/tmp/rx_2.4e9.sigmf-data", data.to_str().unwrap());
+}
+
+#[test]
+fn datetime() {
+    assert_eq!("1970-01-01T00:00:00.000000Z", sigmf::iso8601((0, 0.0)));
+    assert_eq!("2021-03-04T05:06:07.250000Z", sigmf::iso8601((1614834367, 0.25)));
+    assert_eq!("2000-02-29T23:59:59.999999Z", sigmf::iso8601((951868799, 0.999999)));
+
+    assert_eq!(Some((1614834367, 0.25)), sigmf::parse_iso8601("2021-03-04T05:06:07.25Z"));
+    assert_eq!(Some((951868799, 0.0)), sigmf::parse_iso8601("2000-02-29T23:59:59Z"));
+    assert_eq!(None, sigmf::parse_iso8601("2000-02-29T23:59:59"));
+    assert_eq!(None, sigmf::parse_iso8601("2000-13-01T00:00:00Z"));
+}
+
+#[test]
+fn write_with_gap() {
+    let dir = TempDir::new("sigmf_gap");
+    let base = dir.join("capture");
+    let mut writer = SigMfWriter::create(&base, Global::ci16(1e6, 1)).unwrap();
+    writer.device_time_is_utc = true;
+    writer.start_capture(Capture::new(2.4e9, 30.0));
+
+    writer.write_sc16_at(&[(1, -1); 100], (10, 0.0)).unwrap();
+    writer.write_sc16_at(&[(2, -2); 100], (10, 100e-6)).unwrap();
+    writer.write_sc16_at(&[(3, -3); 100], (10, 250e-6)).unwrap();
+    writer.annotate_overflow();
+    assert_eq!(300, writer.samples_written());
+
+    let meta = writer.finish().unwrap();
+    assert_eq!(2, meta.captures.len());
+    assert_eq!(200, meta.captures[1].sample_start);
+    assert_eq!(Some((10, 250e-6)), meta.captures[1].time_spec);
+    assert_eq!(Some(2.4e9), meta.captures[1].frequency);
+    assert_eq!(Some("1970-01-01T00:00:10.000000Z"), meta.captures[0].datetime.as_deref());
+
+    assert_eq!(2, meta.annotations.len());
+    assert_eq!(Some("gap"), meta.annotations[0].label.as_deref());
+    assert_eq!(Some("overflow"), meta.annotations[1].label.as_deref());
+
+    let (data_path, meta_path) = sigmf::paths(&base);
+    let data = std::fs::read(&data_path).unwrap();
+    assert_eq!(1200, data.len());
+    assert_eq!(&[1, 0, 0xFF, 0xFF], &data[..4]);
+
+    let json = std::fs::read_to_string(&meta_path).unwrap();
+    assert!(json.contains("\"core:datatype\": \"ci16_le\""));
+    assert_eq!(meta, SigMfMeta::from_json(&json).unwrap());
+}
+
+#[test]
+fn read_back() {
+    let dir = TempDir::new("sigmf_read");
+    let base = dir.join("capture");
+    let mut writer = SigMfWriter::create(&base, Global::ci16(1e3, 2)).unwrap();
+    writer.start_capture(Capture::new(915e6, 10.0));
+    writer.write_sc16_at(&[(1, 2), (3, 4), (5, 6), (7, 8)], (100, 0.0)).unwrap();
+    writer.write_sc16_at(&[(9, 10), (11, 12)], (101, 0.0)).unwrap();
+    writer.finish().unwrap();
+
+    let reader = SigMfReader::open(sigmf::paths(&base).1).unwrap();
+    assert_eq!(Datatype::Ci16Le, reader.datatype);
+    assert_eq!(3, reader.num_samples());
+    assert_eq!(&[(1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12)], reader.ci16().unwrap());
+    assert!(reader.cf32().is_err());
+
+    let segments:Vec<_> = reader.captures().map(|seg| seg.samples).collect();
+    assert_eq!(vec![0..2, 2..3], segments);
+    assert_eq!(1, reader.annotations_in(2..3).count());
+    assert_eq!(0, reader.annotations_in(0..2).count());
+
+    assert_eq!(Some((100, 1e-3)), reader.time_of_sample(1));
+    assert_eq!(Some((101, 0.0)), reader.time_of_sample(2));
+    assert_eq!(Some(2), reader.sample_at_time((101, 0.0)));
+    assert_eq!(None, reader.sample_at_time((100, 0.5)));
+}
@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::convert;
+use crate::io::sigmf::{self, Annotation, Capture, Datatype, Global, SigMfMeta};
+use crate::timing::time_sync::{time_add, time_diff};
+use crate::types::TimeSpec;
+
+// Writes a `.sigmf-data` file as samples arrive and the matching `.sigmf-meta` file when finished
+pub struct SigMfWriter {
+    data:BufWriter<File>,
+    data_path:PathBuf,
+    meta_path:PathBuf,
+    meta:SigMfMeta,
+    samples_written:u64,
+    bytes_written:u64,
+    // Only set `core:datetime` when device time is known to count from the Unix epoch
+    pub device_time_is_utc:bool,
+}
+
+impl SigMfWriter {
+
+    pub fn create<P: AsRef<Path>>(path:P, global:Global) -> Result<Self, &'static str> {
+        if global.sample_rate.map(|r| r <= 0.0).unwrap_or(false) {
+            return Err("SigMF sample rate must be positive");
+        }
+        let (data_path, meta_path) = sigmf::paths(path);
+        let data = File::create(&data_path).map_err(|_| "Unable to create SigMF data file")?;
+        Ok(Self {
+            data: BufWriter::new(data),
+            data_path, meta_path,
+            meta: SigMfMeta{ global, captures: vec![], annotations: vec![] },
+            samples_written: 0,
+            bytes_written: 0,
+            device_time_is_utc: false,
+        })
+    }
+
+    pub fn meta(&self) -> &SigMfMeta { &self.meta }
+    pub fn meta_mut(&mut self) -> &mut SigMfMeta { &mut self.meta }
+    pub fn data_path(&self) -> &Path { &self.data_path }
+    pub fn meta_path(&self) -> &Path { &self.meta_path }
+    pub fn samples_written(&self) -> u64 { self.samples_written }
+    pub fn bytes_written(&self) -> u64 { self.bytes_written }
+
+    // Reserves disk space up front so a long recording doesn't fragment or run out partway through a file; whatever
+    // isn't used is trimmed off by `finish`
+    pub fn preallocate(&mut self, bytes:u64) -> Result<(), &'static str> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::io::AsRawFd;
+            let fd = self.data.get_ref().as_raw_fd();
+            if unsafe { libc::posix_fallocate(fd, 0, bytes as libc::off_t) } != 0 {
+                return Err("Unable to preallocate SigMF data file");
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = bytes;
+        Ok(())
+    }
+
+    fn num_channels(&self) -> u64 { self.meta.global.num_channels.unwrap_or(1).max(1) as u64 }
+
+    // Starts a capture segment at the current position, replacing one that hasn't had any samples yet
+    pub fn start_capture(&mut self, mut capture:Capture) {
+        capture.sample_start = self.samples_written;
+        if let Some(time_spec) = capture.time_spec {
+            if self.device_time_is_utc && capture.datetime.is_none() {
+                capture.datetime = Some(sigmf::iso8601(time_spec));
+            }
+        }
+        if self.meta.captures.last().map(|c| c.sample_start == capture.sample_start).unwrap_or(false) {
+            self.meta.captures.pop();
+        }
+        self.meta.captures.push(capture);
+    }
+
+    pub fn annotate(&mut self, annotation:Annotation) {
+        self.meta.annotations.push(annotation);
+    }
+
+    // Marks the current position, e.g. after the streamer reported an overflow
+    pub fn annotate_overflow(&mut self) {
+        self.annotate(Annotation::new(self.samples_written, None, "overflow", "Receive overflow reported by UHD"));
+    }
+
+    // With multiple channels, `samples` holds one sample per channel per time step
+    pub fn write_sc16(&mut self, samples:&[(i16, i16)]) -> Result<(), &'static str> {
+        self.write_iq(samples, Datatype::Ci16Le)
+    }
+
+    // For recordings created with a `cf32_le` datatype, e.g. from `Global::cf32`
+    pub fn write_cf32(&mut self, samples:&[(f32, f32)]) -> Result<(), &'static str> {
+        self.write_iq(samples, Datatype::Cf32Le)
+    }
+
+    fn write_iq<T: convert::Sample>(&mut self, samples:&[(T, T)], datatype:Datatype) -> Result<(), &'static str> {
+        if Datatype::parse(&self.meta.global.datatype)? != datatype {
+            return Err("Samples don't match the SigMF recording's datatype");
+        }
+        let num_channels = self.num_channels();
+        let steps = samples.len() as u64 / num_channels;
+        if steps * num_channels != samples.len() as u64 {
+            return Err("Interleaved samples must contain a whole number of samples per channel");
+        }
+        if self.meta.captures.is_empty() {
+            self.start_capture(Capture::default());
+        }
+        let bytes = convert::iq_to_le_bytes(samples);
+        self.data.write_all(&bytes).map_err(|_| "Unable to write to SigMF data file")?;
+        self.samples_written += steps;
+        self.bytes_written += bytes.len() as u64;
+        Ok(())
+    }
+
+    // Like `write_sc16`, but checks `time_spec` (the time of the first sample) against where the stream should be.
+    // A jump starts a new capture segment at the new time and is annotated as a gap or as time going backwards.
+    pub fn write_sc16_at(&mut self, samples:&[(i16, i16)], time_spec:TimeSpec) -> Result<(), &'static str> {
+        match (self.expected_time(), self.meta.global.sample_rate) {
+            (Some(expected), Some(rate)) => {
+                let offset = (time_diff(time_spec, expected) * rate).round() as i64;
+                if offset != 0 {
+                    let comment = match offset > 0 {
+                        true => format!("{} samples missing before this point", offset),
+                        false => format!("Time stepped back by {} samples", -offset),
+                    };
+                    let label = if offset > 0 { "gap" } else { "discontinuity" };
+                    self.annotate(Annotation::new(self.samples_written, None, label, &comment));
+
+                    let last = self.meta.captures.last().cloned().unwrap_or_default();
+                    self.start_capture(Capture{ time_spec: Some(time_spec), datetime: None, ..last });
+                }
+            },
+            _ => {
+                let mut capture = self.meta.captures.last().cloned().unwrap_or_default();
+                capture.time_spec = Some(time_spec);
+                capture.datetime = None;
+                // Only the first write into a segment can give it a start time
+                if self.meta.captures.last().map(|c| c.sample_start == self.samples_written).unwrap_or(true) {
+                    self.start_capture(capture);
+                }
+            }
+        }
+        self.write_sc16(samples)
+    }
+
+    // Time of the next sample according to the current capture segment, if it has a start time and the rate is known
+    pub fn expected_time(&self) -> Option<TimeSpec> {
+        let capture = self.meta.captures.last()?;
+        let rate = self.meta.global.sample_rate?;
+        let start = capture.time_spec?;
+        Some(time_add(start, (self.samples_written - capture.sample_start) as f64 / rate))
+    }
+
+    // Flushes samples and writes the metadata file next to them
+    pub fn finish(mut self) -> Result<SigMfMeta, &'static str> {
+        self.data.flush().map_err(|_| "Unable to flush SigMF data file")?;
+        self.data.get_ref().set_len(self.bytes_written).map_err(|_| "Unable to trim SigMF data file")?;
+        if self.meta.captures.is_empty() {
+            self.meta.captures.push(Capture::default());
+        }
+        std::fs::write(&self.meta_path, self.meta.to_json()?).map_err(|_| "Unable to write SigMF metadata file")?;
+        Ok(self.meta)
+    }
+
+}
@@ -0,0 +1,229 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::convert::{self, IntFormat};
+use crate::io::codec::IqCodec;
+use crate::io::npy::{NpyDtype, NpyReader, NpyWriter};
+use crate::io::sigmf::{Datatype, Global, SigMfMeta, SigMfReader, SigMfWriter};
+use crate::io::wav::{WavFormat, WavReader, WavWriter};
+
+#[cfg(test)]
+mod tests;
+
+// Samples moved per step, so memory use doesn't depend on the file size
+const CHUNK:usize = 65536;
+
+// Raw formats are headerless interleaved little-endian I/Q, as written by `io::write_sc16_to_file`.
+// WAV output can be opened in Audacity or with MATLAB's `audioread` (I and Q are the left and right channels),
+// and `.npy` output with `numpy.load`. SigMF output keeps float samples as `cf32_le` and writes `ci16_le` otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IqFormat { Sc16, Fc32, Wav(WavFormat), Npy(NpyDtype), SigMf }
+
+impl IqFormat {
+
+    // Guesses from the extension; raw files are taken to be sc16 unless they're named .fc32 or .cf32
+    pub fn from_path<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let ext = path.as_ref().extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+        match ext.as_deref() {
+            Some("bin") | Some("dat") | Some("sc16") | Some("ci16") => Ok(IqFormat::Sc16),
+            Some("fc32") | Some("cf32") => Ok(IqFormat::Fc32),
+            Some("wav") => Ok(IqFormat::Wav(WavFormat::Pcm16)),
+            Some("npy") => Ok(IqFormat::Npy(NpyDtype::Complex64)),
+            Some("sigmf-data") | Some("sigmf-meta") | Some("sigmf") => Ok(IqFormat::SigMf),
+            _ => Err("Unable to tell the I/Q format from the file extension"),
+        }
+    }
+
+}
+
+// Each variant keeps whatever buffers it converts through, so they're reused from one chunk to the next
+enum Source {
+    Raw(BufReader<File>, IqFormat, IqCodec),
+    Wav(WavReader),
+    Npy(NpyReader),
+    SigMf(Box<SigMfReader>, u64, Vec<(i16, i16)>),  // Next sample to read
+}
+
+enum Sink {
+    Raw(BufWriter<File>, IqFormat, IqCodec),
+    Wav(WavWriter),
+    Npy(NpyWriter),
+    SigMf(Box<SigMfWriter>, Vec<(i16, i16)>),
+}
+
+impl Source {
+
+    fn open(path:&Path, format:IqFormat) -> Result<Self, &'static str> {
+        match format {
+            IqFormat::Sc16 | IqFormat::Fc32 => Ok(Source::Raw(BufReader::new(File::open(path).map_err(|_| "Unable to open raw I/Q file")?), format, IqCodec::default())),
+            IqFormat::Wav(_) => Ok(Source::Wav(WavReader::open(path)?)),
+            IqFormat::Npy(_) => Ok(Source::Npy(NpyReader::open(path)?)),
+            IqFormat::SigMf => {
+                let reader = SigMfReader::open(path)?;
+                if reader.num_channels() != 1 {
+                    return Err("Only single-channel SigMF recordings can be converted");
+                }
+                Ok(Source::SigMf(Box::new(reader), 0, vec![]))
+            },
+        }
+    }
+
+    fn sample_rate(&self) -> Option<f64> {
+        match self {
+            Source::Wav(reader) => Some(reader.sample_rate() as f64),
+            Source::SigMf(reader, ..) => reader.sample_rate(),
+            _ => None,
+        }
+    }
+
+    // Returns the number of samples read, which is zero at the end of the input
+    fn read(&mut self, buff:&mut [(f32, f32)]) -> Result<usize, &'static str> {
+        match self {
+            Source::Raw(file, format, codec) => {
+                let float = *format == IqFormat::Fc32;
+                let size = IqCodec::sample_size(float);
+                let n = read_full(file, codec.bytes_mut(buff.len(), float))?;
+                if n != (n / size) * size {
+                    return Err("Raw I/Q file doesn't hold a whole number of samples");
+                }
+                codec.decode_fc32(&mut buff[..n / size], float)?;
+                Ok(n / size)
+            },
+            Source::Wav(reader) => reader.read_fc32(buff),
+            Source::Npy(reader) => reader.read_fc32(buff),
+            Source::SigMf(reader, next, sc16) => {
+                let start = *next as usize;
+                let n = (reader.num_samples() as usize - start).min(buff.len());
+                let dst = convert::flatten_mut(&mut buff[..n])?;
+                match reader.datatype {
+                    Datatype::Ci16Le => convert::int_to_float(convert::flatten(&reader.ci16()?[start..start + n])?, IntFormat::Sc16, dst, 1.0)?,
+                    Datatype::Cf32Le => dst.copy_from_slice(convert::flatten(&reader.cf32()?[start..start + n])?),
+                    // Through sc16 the way UHD widens sc8, so ci8 plays and converts at the same level
+                    Datatype::Ci8 => {
+                        sc16.resize(n, (0, 0));
+                        convert::int_to_int(convert::flatten(&reader.ci8()?[start..start + n])?, IntFormat::Sc8, convert::flatten_mut(sc16)?, IntFormat::Sc16)?;
+                        convert::int_to_float(convert::flatten(sc16)?, IntFormat::Sc16, dst, 1.0)?;
+                    },
+                }
+                *next += n as u64;
+                Ok(n)
+            },
+        }
+    }
+
+    // Whether samples would lose precision or range as sc16
+    fn is_float(&self) -> bool {
+        match self {
+            Source::Raw(_, format, _) => *format == IqFormat::Fc32,
+            Source::Wav(reader) => reader.format() == WavFormat::Float32,
+            Source::Npy(reader) => reader.dtype() == NpyDtype::Complex64,
+            Source::SigMf(reader, ..) => reader.datatype == Datatype::Cf32Le,
+        }
+    }
+
+    // Captures and annotations carry over unchanged since sample indices are the same on both sides
+    fn sigmf_meta(&self) -> Option<&SigMfMeta> {
+        match self {
+            Source::SigMf(reader, ..) => Some(&reader.meta),
+            _ => None,
+        }
+    }
+
+}
+
+// Like `read_exact`, but a short read at the end of the file isn't an error
+fn read_full<R: Read>(reader:&mut R, buff:&mut [u8]) -> Result<usize, &'static str> {
+    let mut n = 0;
+    while n < buff.len() {
+        match reader.read(&mut buff[n..]) {
+            Ok(0) => break,
+            Ok(k) => n += k,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(_) => return Err("Unable to read from raw I/Q file"),
+        }
+    }
+    Ok(n)
+}
+
+impl Sink {
+
+    fn create(path:&Path, format:IqFormat, sample_rate:Option<f64>, float:bool) -> Result<Self, &'static str> {
+        match format {
+            IqFormat::Sc16 | IqFormat::Fc32 => Ok(Sink::Raw(BufWriter::new(File::create(path).map_err(|_| "Unable to create raw I/Q file")?), format, IqCodec::default())),
+            IqFormat::Wav(wav_format) => {
+                let rate = sample_rate.ok_or("WAV output needs a sample rate")?.round();
+                if rate < 1.0 || rate > u32::MAX as f64 {
+                    return Err("Sample rate doesn't fit in a WAV header");
+                }
+                Ok(Sink::Wav(WavWriter::create(path, rate as u32, wav_format)?))
+            },
+            IqFormat::Npy(dtype) => Ok(Sink::Npy(NpyWriter::create(path, dtype)?)),
+            IqFormat::SigMf => {
+                let mut global = if float { Global::cf32(0.0, 1) } else { Global::ci16(0.0, 1) };
+                global.sample_rate = sample_rate;
+                Ok(Sink::SigMf(Box::new(SigMfWriter::create(path, global)?), vec![]))
+            },
+        }
+    }
+
+    fn write(&mut self, samples:&[(f32, f32)]) -> Result<(), &'static str> {
+        match self {
+            Sink::Raw(file, format, codec) => {
+                let bytes = codec.encode_fc32(samples, *format == IqFormat::Fc32)?;
+                file.write_all(bytes).map_err(|_| "Unable to write to raw I/Q file")
+            },
+            Sink::Wav(writer) => writer.write_fc32(samples),
+            Sink::Npy(writer) => writer.write_fc32(samples),
+            Sink::SigMf(writer, _) if writer.meta().global.datatype == "cf32_le" => writer.write_cf32(samples),
+            Sink::SigMf(writer, sc16) => {
+                sc16.resize(samples.len(), (0, 0));
+                convert::float_to_int(convert::flatten(samples)?, convert::flatten_mut(sc16)?, IntFormat::Sc16, 1.0)?;
+                writer.write_sc16(sc16)
+            },
+        }
+    }
+
+    fn finish(self, source_meta:Option<&SigMfMeta>) -> Result<(), &'static str> {
+        match self {
+            Sink::Raw(mut file, ..) => file.flush().map_err(|_| "Unable to flush raw I/Q file"),
+            Sink::Wav(writer) => writer.finish(),
+            Sink::Npy(writer) => writer.finish(),
+            Sink::SigMf(mut writer, _) => {
+                if let Some(meta) = source_meta {
+                    let global = &mut writer.meta_mut().global;
+                    global.hw = meta.global.hw.clone();
+                    global.description = meta.global.description.clone();
+                    global.author = meta.global.author.clone();
+                    writer.meta_mut().captures = meta.captures.clone();
+                    writer.meta_mut().annotations = meta.annotations.clone();
+                }
+                writer.finish().map(|_| ())
+            },
+        }
+    }
+
+}
+
+// Converts `input` to `output` a chunk at a time through fc32, which carries sc16 through unchanged.
+// The sample rate comes from the input when it has one (WAV, SigMF) and otherwise from `sample_rate`.
+// Returns the number of samples converted.
+pub fn transcode<P: AsRef<Path>, Q: AsRef<Path>>(input:P, in_format:IqFormat, output:Q, out_format:IqFormat, sample_rate:Option<f64>) -> Result<u64, &'static str> {
+    let mut source = Source::open(input.as_ref(), in_format)?;
+    let rate = source.sample_rate().or(sample_rate);
+    let mut sink = Sink::create(output.as_ref(), out_format, rate, source.is_float())?;
+
+    let mut buff = vec![(0.0, 0.0); CHUNK];
+    let mut total = 0;
+    loop {
+        let n = source.read(&mut buff)?;
+        if n == 0 {
+            break;
+        }
+        sink.write(&buff[..n])?;
+        total += n as u64;
+    }
+
+    sink.finish(source.sigmf_meta())?;
+    Ok(total)
+}
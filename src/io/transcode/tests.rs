@@ -0,0 +1,64 @@
+use crate::io;
+use crate::io::npy::NpyDtype;
+use crate::io::sigmf::SigMfReader;
+use crate::io::transcode::{self, IqFormat};
+use crate::io::wav::{WavFormat, WavReader};
+use crate::test_util::TempDir;
+
+#[test]
+fn formats_from_path() {
+    assert_eq!(Ok(IqFormat::Sc16), IqFormat::from_path("rx_A0.bin"));
+    assert_eq!(Ok(IqFormat::Fc32), IqFormat::from_path("capture.CF32"));
+    assert_eq!(Ok(IqFormat::Wav(WavFormat::Pcm16)), IqFormat::from_path("capture.wav"));
+    assert_eq!(Ok(IqFormat::Npy(NpyDtype::Complex64)), IqFormat::from_path("capture.npy"));
+    assert_eq!(Ok(IqFormat::SigMf), IqFormat::from_path("capture.sigmf-meta"));
+    assert!(IqFormat::from_path("capture.txt").is_err());
+}
+
+#[test]
+fn chain() {
+    let dir = TempDir::new("transcode");
+    let samples:Vec<(i16, i16)> = (0..100_000).map(|n| ((n % 65536 - 32768) as i16, (n % 1000) as i16)).collect();
+    crate::io::write_sc16_to_file(dir.join("in.bin"), &samples).unwrap();
+
+    // WAV needs a rate from somewhere
+    assert!(transcode::transcode(dir.join("in.bin"), IqFormat::Sc16, dir.join("a.wav"), IqFormat::Wav(WavFormat::Pcm16), None).is_err());
+
+    assert_eq!(100_000, transcode::transcode(dir.join("in.bin"), IqFormat::Sc16, dir.join("a.wav"), IqFormat::Wav(WavFormat::Pcm16), Some(1.0e6)).unwrap());
+    assert_eq!(1_000_000, WavReader::open(dir.join("a.wav")).unwrap().sample_rate());
+
+    transcode::transcode(dir.join("a.wav"), IqFormat::Wav(WavFormat::Pcm16), dir.join("b.npy"), IqFormat::Npy(NpyDtype::Complex64), None).unwrap();
+    transcode::transcode(dir.join("b.npy"), IqFormat::Npy(NpyDtype::Complex64), dir.join("c.fc32"), IqFormat::Fc32, None).unwrap();
+    transcode::transcode(dir.join("c.fc32"), IqFormat::Fc32, dir.join("d"), IqFormat::SigMf, Some(1.0e6)).unwrap();
+
+    // Float input stays float in SigMF
+    let reader = SigMfReader::open(dir.join("d")).unwrap();
+    assert_eq!(Some(1.0e6), reader.sample_rate());
+    let back:Vec<(i16, i16)> = reader.cf32().unwrap().iter().map(|(i, q)| (io::fc32_to_sc16(*i), io::fc32_to_sc16(*q))).collect();
+    assert_eq!(samples, back);
+
+    transcode::transcode(dir.join("in.bin"), IqFormat::Sc16, dir.join("f"), IqFormat::SigMf, Some(1.0e6)).unwrap();
+    assert_eq!(&samples[..], SigMfReader::open(dir.join("f")).unwrap().ci16().unwrap());
+
+    // Back out of SigMF, picking the rate up from its metadata
+    transcode::transcode(dir.join("d"), IqFormat::SigMf, dir.join("e.wav"), IqFormat::Wav(WavFormat::Float32), None).unwrap();
+    transcode::transcode(dir.join("e.wav"), IqFormat::Wav(WavFormat::Float32), dir.join("out.bin"), IqFormat::Sc16, None).unwrap();
+    assert_eq!(std::fs::read(dir.join("in.bin")).unwrap(), std::fs::read(dir.join("out.bin")).unwrap());
+}
+
+#[test]
+fn float_sigmf_is_lossless() {
+    let dir = TempDir::new("transcode_cf32");
+
+    // Out of sc16 range and below its resolution
+    let samples:Vec<(f32, f32)> = (0..1000).map(|n| (n as f32 * 0.01 - 3.0, 1.0e-7 * n as f32)).collect();
+    std::fs::write(dir.join("in.fc32"), crate::convert::iq_to_le_bytes(&samples)).unwrap();
+
+    transcode::transcode(dir.join("in.fc32"), IqFormat::Fc32, dir.join("a"), IqFormat::SigMf, Some(1.0e6)).unwrap();
+    let reader = SigMfReader::open(dir.join("a")).unwrap();
+    assert_eq!("cf32_le", reader.meta.global.datatype);
+    assert_eq!(&samples[..], reader.cf32().unwrap());
+
+    transcode::transcode(dir.join("a"), IqFormat::SigMf, dir.join("out.fc32"), IqFormat::Fc32, None).unwrap();
+    assert_eq!(std::fs::read(dir.join("in.fc32")).unwrap(), std::fs::read(dir.join("out.fc32")).unwrap());
+}
@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::io::codec::IqCodec;
+
+#[cfg(test)]
+mod tests;
+
+// Stereo WAV with I on the left channel and Q on the right, which Audacity and MATLAB's `audioread` open directly
+
+const FORMAT_PCM:u16 = 1;
+const FORMAT_FLOAT:u16 = 3;
+const HEADER_LEN:u64 = 44;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WavFormat { Pcm16, Float32 }
+
+impl WavFormat {
+
+    fn is_float(&self) -> bool { *self == WavFormat::Float32 }
+
+    fn bytes_per_frame(&self) -> u64 { IqCodec::sample_size(self.is_float()) as u64 }
+
+}
+
+// Writes the header up front with placeholder sizes and fills them in on `finish`, so samples can be streamed
+pub struct WavWriter {
+    file:BufWriter<File>,
+    format:WavFormat,
+    frames:u64,
+    codec:IqCodec,
+}
+
+pub struct WavReader {
+    file:BufReader<File>,
+    format:WavFormat,
+    sample_rate:u32,
+    frames:u64,
+    frames_read:u64,
+    codec:IqCodec,
+}
+
+impl WavWriter {
+
+    pub fn create<P: AsRef<Path>>(path:P, sample_rate:u32, format:WavFormat) -> Result<Self, &'static str> {
+        let file = File::create(path).map_err(|_| "Unable to create WAV file")?;
+        let mut ans = Self{ file: BufWriter::new(file), format, frames: 0, codec: IqCodec::default() };
+        ans.write_header(sample_rate)?;
+        Ok(ans)
+    }
+
+    fn write_header(&mut self, sample_rate:u32) -> Result<(), &'static str> {
+        let (tag, bits) = match self.format {
+            WavFormat::Pcm16 => (FORMAT_PCM, 16u16),
+            WavFormat::Float32 => (FORMAT_FLOAT, 32u16),
+        };
+        let block_align = self.format.bytes_per_frame() as u16;
+
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&tag.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&sample_rate.to_le_bytes());
+        header.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&bits.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        self.file.write_all(&header).map_err(|_| "Unable to write WAV header")
+    }
+
+    pub fn frames(&self) -> u64 { self.frames }
+
+    pub fn write_sc16(&mut self, samples:&[(i16, i16)]) -> Result<(), &'static str> {
+        self.check_room(samples.len())?;
+        let bytes = self.codec.encode_sc16(samples, self.format.is_float())?;
+        self.file.write_all(bytes).map_err(|_| "Unable to write to WAV file")?;
+        self.frames += samples.len() as u64;
+        Ok(())
+    }
+
+    pub fn write_fc32(&mut self, samples:&[(f32, f32)]) -> Result<(), &'static str> {
+        self.check_room(samples.len())?;
+        let bytes = self.codec.encode_fc32(samples, self.format.is_float())?;
+        self.file.write_all(bytes).map_err(|_| "Unable to write to WAV file")?;
+        self.frames += samples.len() as u64;
+        Ok(())
+    }
+
+    // Refuses up front to go past what the 32-bit RIFF sizes can describe, rather than after writing it all
+    fn check_room(&self, frames:usize) -> Result<(), &'static str> {
+        let data_len = (self.frames + frames as u64) * self.format.bytes_per_frame();
+        match data_len + HEADER_LEN - 8 > u32::MAX as u64 {
+            true => Err("Too many samples for a WAV file"),
+            false => Ok(()),
+        }
+    }
+
+    pub fn finish(mut self) -> Result<(), &'static str> {
+        let data_len = self.frames * self.format.bytes_per_frame();
+        self.file.flush().map_err(|_| "Unable to flush WAV file")?;
+        let file = self.file.get_mut();
+        file.seek(SeekFrom::Start(4)).map_err(|_| "Unable to seek in WAV file")?;
+        file.write_all(&((data_len + HEADER_LEN - 8) as u32).to_le_bytes()).map_err(|_| "Unable to write WAV header")?;
+        file.seek(SeekFrom::Start(HEADER_LEN - 4)).map_err(|_| "Unable to seek in WAV file")?;
+        file.write_all(&(data_len as u32).to_le_bytes()).map_err(|_| "Unable to write WAV header")
+    }
+
+}
+
+impl WavReader {
+
+    pub fn open<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let mut file = BufReader::new(File::open(path).map_err(|_| "Unable to open WAV file")?);
+        let mut riff = [0u8; 12];
+        file.read_exact(&mut riff).map_err(|_| "WAV file is too short")?;
+        if &riff[..4] != b"RIFF" || &riff[8..] != b"WAVE" {
+            return Err("Not a WAV file");
+        }
+
+        // Chunks can come in any order and there can be others (like LIST) that we skip
+        let mut fmt:Option<(WavFormat, u32)> = None;
+        loop {
+            let mut chunk = [0u8; 8];
+            file.read_exact(&mut chunk).map_err(|_| "WAV file has no data chunk")?;
+            let len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+            match &chunk[..4] {
+                b"fmt " => {
+                    let mut body = vec![0u8; len as usize];
+                    file.read_exact(&mut body).map_err(|_| "WAV fmt chunk is too short")?;
+                    if body.len() < 16 {
+                        return Err("WAV fmt chunk is too short");
+                    }
+                    let field = |idx:usize| u16::from_le_bytes([body[idx], body[idx + 1]]);
+                    let format = match (field(0), field(2), field(14)) {
+                        (FORMAT_PCM, 2, 16) => WavFormat::Pcm16,
+                        (FORMAT_FLOAT, 2, 32) => WavFormat::Float32,
+                        _ => return Err("Only stereo 16-bit PCM or 32-bit float WAV files hold I/Q"),
+                    };
+                    fmt = Some((format, u32::from_le_bytes([body[4], body[5], body[6], body[7]])));
+                },
+                b"data" => {
+                    let (format, sample_rate) = fmt.ok_or("WAV data chunk comes before the fmt chunk")?;
+                    let frames = len / format.bytes_per_frame();
+                    return Ok(Self{ file, format, sample_rate, frames, frames_read: 0, codec: IqCodec::default() });
+                },
+                _ => {
+                    // Chunks are padded to an even length
+                    file.seek_relative((len + len % 2) as i64).map_err(|_| "Unable to skip WAV chunk")?;
+                },
+            }
+        }
+    }
+
+    pub fn format(&self) -> WavFormat { self.format }
+    pub fn sample_rate(&self) -> u32 { self.sample_rate }
+    pub fn frames(&self) -> u64 { self.frames }
+
+    // Reads up to `max` frames into the codec's buffer
+    fn read_frames(&mut self, max:usize) -> Result<usize, &'static str> {
+        let n = (self.frames - self.frames_read).min(max as u64) as usize;
+        let bytes = self.codec.bytes_mut(n, self.format.is_float());
+        self.file.read_exact(bytes).map_err(|_| "WAV file is shorter than its header says")?;
+        self.frames_read += n as u64;
+        Ok(n)
+    }
+
+    // Returns the number of samples read, which is zero at the end of the file
+    pub fn read_sc16(&mut self, buff:&mut [(i16, i16)]) -> Result<usize, &'static str> {
+        let n = self.read_frames(buff.len())?;
+        self.codec.decode_sc16(&mut buff[..n], self.format.is_float())?;
+        Ok(n)
+    }
+
+    pub fn read_fc32(&mut self, buff:&mut [(f32, f32)]) -> Result<usize, &'static str> {
+        let n = self.read_frames(buff.len())?;
+        self.codec.decode_fc32(&mut buff[..n], self.format.is_float())?;
+        Ok(n)
+    }
+
+}
@@ -0,0 +1,47 @@
+use crate::io::wav::{WavFormat, WavReader, WavWriter};
+use crate::test_util::TempDir;
+
+#[test]
+fn round_trip() {
+    let dir = TempDir::new("wav");
+    for format in [WavFormat::Pcm16, WavFormat::Float32] {
+        let path = dir.join(format!("{:?}.wav", format));
+        let samples:Vec<(i16, i16)> = (0..1000).map(|n| (n as i16 * 30, -(n as i16) * 30)).collect();
+
+        let mut writer = WavWriter::create(&path, 250_000, format).unwrap();
+        writer.write_sc16(&samples[..600]).unwrap();
+        writer.write_sc16(&samples[600..]).unwrap();
+        writer.finish().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(44 + 1000 * if format == WavFormat::Pcm16 { 4 } else { 8 }, bytes.len());
+        assert_eq!(&bytes[..4], b"RIFF");
+
+        let mut reader = WavReader::open(&path).unwrap();
+        assert_eq!(format, reader.format());
+        assert_eq!(250_000, reader.sample_rate());
+        assert_eq!(1000, reader.frames());
+
+        let mut buff = vec![(0, 0); 700];
+        assert_eq!(700, reader.read_sc16(&mut buff).unwrap());
+        assert_eq!(&samples[..700], &buff[..]);
+        assert_eq!(300, reader.read_sc16(&mut buff).unwrap());
+        assert_eq!(&samples[700..], &buff[..300]);
+        assert_eq!(0, reader.read_sc16(&mut buff).unwrap());
+    }
+}
+
+#[test]
+fn size_limit_checked_before_writing() {
+    let dir = TempDir::new("wav_limit");
+    let path = dir.join("limit.wav");
+    let mut writer = WavWriter::create(&path, 1_000_000, WavFormat::Pcm16).unwrap();
+
+    // Pretend the file is already just short of the 4 GiB RIFF limit
+    writer.frames = (u32::MAX as u64 - 36) / 4 - 10;
+    assert!(writer.write_sc16(&[(0, 0); 10]).is_ok());
+    assert!(writer.write_sc16(&[(0, 0); 1]).is_err());
+    drop(writer);
+
+    assert_eq!(44 + 40, std::fs::metadata(&path).unwrap().len());
+}
@@ -12,14 +12,27 @@ fn check_err<T>(t:T, result:isize) -> Result<T, &'static str> {
 }
 
 pub mod c_interop;
+pub mod convert;
+pub mod corrections;
 pub mod io;
+pub mod lo_sharing;
+pub mod player;
+pub mod probe;
+pub mod radio_config;
+pub mod recorder;
+pub mod retune;
 
 pub mod error;
 
 pub mod rx_streamer;
+pub mod schedule;
+pub mod sensor_monitor;
 pub mod tx_streamer;
 pub mod usrp;
 
 pub mod types;
 
-pub mod timing;
\ No newline at end of file
+pub mod timing;
+
+#[cfg(test)]
+mod test_util;
\ No newline at end of file
@@ -0,0 +1,156 @@
+use crate::usrp::USRP;
+
+#[cfg(test)]
+mod tests;
+
+pub const ALL_LOS:&str = "all";
+
+// LO frequencies read back from channels sharing an LO should be identical; this only absorbs float noise
+const FREQ_TOLERANCE_HZ:f64 = 1.0;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction { Rx, Tx }
+
+// One channel exports its LO and every other channel in the group imports it, which is what's needed for
+// phase-coherent operation on boards like the TwinRX
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoSharing {
+    pub direction:Direction,
+    pub lo_name:String,
+    pub exporter:usize,
+    pub importers:Vec<(usize, String)>,     // Channel and the LO source it should select
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelLoStatus {
+    pub chan:usize,
+    pub expected_source:String,
+    pub reported_source:String,
+    pub lo_freq:Option<f64>,                // None if the device couldn't report it
+}
+
+impl ChannelLoStatus {
+
+    pub fn source_ok(&self) -> bool {
+        self.expected_source == self.reported_source
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoSharingReport {
+    pub export_enabled:bool,
+    pub channels:Vec<ChannelLoStatus>,      // Exporter first, then importers in the order they were added
+}
+
+impl LoSharingReport {
+
+    pub fn sources_ok(&self) -> bool {
+        self.channels.iter().all(|c| c.source_ok())
+    }
+
+    pub fn lo_freqs_agree(&self) -> bool {
+        let freqs:Vec<f64> = self.channels.iter().filter_map(|c| c.lo_freq).collect();
+        if freqs.len() != self.channels.len() || freqs.is_empty() {
+            return false;
+        }
+        freqs.iter().all(|f| (f - freqs[0]).abs() <= FREQ_TOLERANCE_HZ)
+    }
+
+    pub fn phase_coherent_ready(&self) -> bool {
+        self.export_enabled && self.sources_ok() && self.lo_freqs_agree()
+    }
+
+}
+
+impl LoSharing {
+
+    pub fn new(direction:Direction, exporter:usize) -> Self {
+        Self{ direction, lo_name: ALL_LOS.to_owned(), exporter, importers: vec![] }
+    }
+
+    // TwinRX channels come in pairs on the same daughterboard; the partner of the exporter takes the LO through
+    // the companion path and channels on other daughterboards take it through the external input
+    pub fn twinrx(exporter:usize, importers:&[usize]) -> Self {
+        importers.iter().fold(Self::new(Direction::Rx, exporter), |cfg, chan| {
+            let source = if chan ^ 1 == exporter { "companion" } else { "external" };
+            cfg.import(*chan, source)
+        })
+    }
+
+    pub fn lo_name(mut self, name:&str) -> Self {
+        self.lo_name = name.to_owned();
+        self
+    }
+
+    pub fn import(mut self, chan:usize, source:&str) -> Self {
+        self.importers.push((chan, source.to_owned()));
+        self
+    }
+
+    pub fn apply(&self, usrp:&mut USRP) -> Result<LoSharingReport, &'static str> {
+        if self.importers.iter().any(|(chan, _)| *chan == self.exporter) {
+            return Err("The exporting channel can't also import its own LO");
+        }
+
+        self.set_export_enabled(usrp, true, self.exporter)?;
+        self.set_source(usrp, "internal", self.exporter)?;
+        for (chan, source) in self.importers.iter() {
+            self.set_source(usrp, source, *chan)?;
+        }
+
+        self.verify(usrp)
+    }
+
+    pub fn verify(&self, usrp:&USRP) -> Result<LoSharingReport, &'static str> {
+        let export_enabled = self.get_export_enabled(usrp, self.exporter)?;
+
+        let mut channels = vec![];
+        let expected = std::iter::once((self.exporter, "internal".to_owned())).chain(self.importers.iter().cloned());
+        for (chan, expected_source) in expected {
+            channels.push(ChannelLoStatus{
+                chan, expected_source,
+                reported_source: self.get_source(usrp, chan)?,
+                lo_freq: self.get_freq(usrp, chan).ok(),
+            });
+        }
+
+        Ok(LoSharingReport{ export_enabled, channels })
+    }
+
+    fn set_export_enabled(&self, usrp:&USRP, en:bool, chan:usize) -> Result<(), &'static str> {
+        match self.direction {
+            Direction::Rx => usrp.set_rx_lo_export_enabled(en, &self.lo_name, chan),
+            Direction::Tx => usrp.set_tx_lo_export_enabled(en, &self.lo_name, chan),
+        }
+    }
+
+    fn get_export_enabled(&self, usrp:&USRP, chan:usize) -> Result<bool, &'static str> {
+        match self.direction {
+            Direction::Rx => usrp.get_rx_lo_export_enabled(&self.lo_name, chan),
+            Direction::Tx => usrp.get_tx_lo_export_enabled(&self.lo_name, chan),
+        }
+    }
+
+    fn set_source(&self, usrp:&USRP, src:&str, chan:usize) -> Result<(), &'static str> {
+        match self.direction {
+            Direction::Rx => usrp.set_rx_lo_source(src, &self.lo_name, chan),
+            Direction::Tx => usrp.set_tx_lo_source(src, &self.lo_name, chan),
+        }
+    }
+
+    fn get_source(&self, usrp:&USRP, chan:usize) -> Result<String, &'static str> {
+        match self.direction {
+            Direction::Rx => usrp.get_rx_lo_source(&self.lo_name, chan),
+            Direction::Tx => usrp.get_tx_lo_source(&self.lo_name, chan),
+        }
+    }
+
+    fn get_freq(&self, usrp:&USRP, chan:usize) -> Result<f64, &'static str> {
+        match self.direction {
+            Direction::Rx => usrp.get_rx_lo_freq(&self.lo_name, chan),
+            Direction::Tx => usrp.get_tx_lo_freq(&self.lo_name, chan),
+        }
+    }
+
+}
@@ -0,0 +1,54 @@
+use crate::lo_sharing::{ChannelLoStatus, Direction, LoSharing, LoSharingReport};
+
+fn status(chan:usize, expected:&str, reported:&str, lo_freq:Option<f64>) -> ChannelLoStatus {
+    ChannelLoStatus{ chan, expected_source: expected.to_owned(), reported_source: reported.to_owned(), lo_freq }
+}
+
+#[test]
+fn twinrx_source_selection() {
+    let cfg = LoSharing::twinrx(2, &[0, 1, 3]);
+    assert_eq!(Direction::Rx, cfg.direction);
+    assert_eq!("all", cfg.lo_name);
+    assert_eq!(cfg.importers, vec![
+        (0, "external".to_owned()),
+        (1, "external".to_owned()),
+        (3, "companion".to_owned()),
+    ]);
+}
+
+#[test]
+fn readiness_requires_every_check() {
+    let mut report = LoSharingReport{
+        export_enabled: true,
+        channels: vec![
+            status(0, "internal", "internal", Some(531.0e6)),
+            status(1, "companion", "companion", Some(531.0e6)),
+        ]
+    };
+    assert!(report.phase_coherent_ready());
+
+    report.channels[1].lo_freq = None;
+    assert!(report.sources_ok());
+    assert!(!report.phase_coherent_ready());
+
+    report.channels[1].lo_freq = Some(531.0e6);
+    report.channels[1].reported_source = "internal".to_owned();
+    assert!(!report.sources_ok());
+    assert!(!report.phase_coherent_ready());
+
+    report.channels[1].reported_source = "companion".to_owned();
+    report.export_enabled = false;
+    assert!(!report.phase_coherent_ready());
+}
+
+#[test]
+fn mismatched_lo_freqs() {
+    let report = LoSharingReport{
+        export_enabled: true,
+        channels: vec![
+            status(0, "internal", "internal", Some(531.0e6)),
+            status(2, "external", "external", Some(531.1e6)),
+        ]
+    };
+    assert!(!report.lo_freqs_agree());
+}
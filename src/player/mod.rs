@@ -0,0 +1,303 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::convert::{self, Endian, IntFormat};
+use crate::io::sigmf::{Datatype, SigMfReader};
+use crate::timing::time_sync::time_diff;
+use crate::tx_streamer::{TxStreamer, DEFAULT_TIMEOUT};
+use crate::types::TimeSpec;
+use crate::types::metadata::{AsyncMetadata, AsyncMetadataEventCode};
+use crate::types::stream_args::StreamArgsBuilder;
+use crate::usrp::USRP;
+
+#[cfg(test)]
+mod tests;
+
+// How long to wait after the end of burst for the device to acknowledge it
+const BURST_ACK_TIMEOUT:Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Repeat { Times(usize), Forever }
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaybackReport {
+    pub passes:usize,
+    pub samples_sent:usize,
+    pub underflows:usize,
+    pub seq_errors:usize,
+    pub time_errors:usize,
+    pub burst_acked:bool,
+}
+
+// Where the samples come from.  Files are read a packet at a time while playing rather than loaded up front.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Samples(Vec<(i16, i16)>),
+    Sc16File(PathBuf),      // Raw little-endian interleaved I/Q, as written by `io::write_sc16_to_file`
+    Fc32File(PathBuf),      // Raw little-endian interleaved float I/Q with full scale at 1.0
+    SigMf(PathBuf),         // Single-channel recording of any SigMF datatype
+}
+
+// Plays a recording out of one TX channel as a single burst, so repeats follow each other without a gap
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player {
+    pub source:Source,
+    pub rate:Option<f64>,           // Sample rate of the recording, if the file says
+    pub chan:usize,
+    pub scale:f64,
+    pub repeat:Repeat,
+    pub start_time:Option<TimeSpec>,
+}
+
+impl PlaybackReport {
+
+    pub fn record(&mut self, event:AsyncMetadataEventCode) {
+        match event {
+            AsyncMetadataEventCode::BurstAck => self.burst_acked = true,
+            AsyncMetadataEventCode::Underflow | AsyncMetadataEventCode::UnderflowInPacket => self.underflows += 1,
+            AsyncMetadataEventCode::SeqError | AsyncMetadataEventCode::SeqErrorInBurst => self.seq_errors += 1,
+            AsyncMetadataEventCode::TimeError => self.time_errors += 1,
+            AsyncMetadataEventCode::UserPayload => (),
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.burst_acked && self.underflows == 0 && self.seq_errors == 0 && self.time_errors == 0
+    }
+
+}
+
+impl Source {
+
+    pub fn num_samples(&self) -> Result<u64, &'static str> {
+        let file_samples = |path:&PathBuf, size:u64| -> Result<u64, &'static str> {
+            let len = std::fs::metadata(path).map_err(|_| "Unable to read sample file")?.len();
+            match (len / size) * size == len {
+                true => Ok(len / size),
+                false => Err("Sample file doesn't hold a whole number of samples"),
+            }
+        };
+        match self {
+            Source::Samples(samples) => Ok(samples.len() as u64),
+            Source::Sc16File(path) => file_samples(path, 4),
+            Source::Fc32File(path) => file_samples(path, 8),
+            Source::SigMf(path) => Ok(SigMfReader::open(path)?.num_samples()),
+        }
+    }
+
+    // Starts a pass over the samples
+    pub(crate) fn chunks(&self) -> Result<Chunks<'_>, &'static str> {
+        let open = |path:&PathBuf| File::open(path).map(BufReader::new).map_err(|_| "Unable to open sample file");
+        Ok(match self {
+            Source::Samples(samples) => Chunks::Samples(samples),
+            Source::Sc16File(path) => Chunks::Sc16(open(path)?, vec![]),
+            Source::Fc32File(path) => Chunks::Fc32(open(path)?, vec![], vec![]),
+            Source::SigMf(path) => {
+                let reader = SigMfReader::open(path)?;
+                if reader.num_channels() != 1 {
+                    return Err("Only single-channel SigMF recordings can be played");
+                }
+                Chunks::SigMf(Box::new(reader), 0)
+            },
+        })
+    }
+
+}
+
+// One pass over a source, converted to sc16 a buffer at a time.  The working buffers are reused from one read to
+// the next.
+pub(crate) enum Chunks<'a> {
+    Samples(&'a [(i16, i16)]),
+    Sc16(BufReader<File>, Vec<u8>),
+    Fc32(BufReader<File>, Vec<u8>, Vec<(f32, f32)>),
+    SigMf(Box<SigMfReader>, usize),
+}
+
+impl Chunks<'_> {
+
+    // Fills as much of `dst` as there are samples left and returns how many that was, so zero at the end of the pass
+    pub(crate) fn read(&mut self, dst:&mut [(i16, i16)]) -> Result<usize, &'static str> {
+        match self {
+            Chunks::Samples(samples) => {
+                let n = dst.len().min(samples.len());
+                dst[..n].copy_from_slice(&samples[..n]);
+                *samples = &samples[n..];
+                Ok(n)
+            },
+            Chunks::Sc16(file, bytes) => {
+                let n = read_samples(file, bytes, dst.len(), 4)?;
+                convert::decode_iq(&bytes[..n * 4], &mut dst[..n], Endian::Little)?;
+                Ok(n)
+            },
+            Chunks::Fc32(file, bytes, floats) => {
+                let n = read_samples(file, bytes, dst.len(), 8)?;
+                floats.resize(n, (0.0, 0.0));
+                convert::decode_iq(&bytes[..n * 8], floats, Endian::Little)?;
+                convert::float_to_int(convert::flatten(floats)?, convert::flatten_mut(&mut dst[..n])?, IntFormat::Sc16, 1.0)?;
+                Ok(n)
+            },
+            Chunks::SigMf(reader, pos) if reader.datatype == Datatype::Ci16Le => {
+                let src = &reader.ci16()?[*pos..];
+                let n = dst.len().min(src.len());
+                dst[..n].copy_from_slice(&src[..n]);
+                *pos += n;
+                Ok(n)
+            },
+            Chunks::SigMf(reader, pos) if reader.datatype == Datatype::Cf32Le => {
+                let src = &reader.cf32()?[*pos..];
+                let n = dst.len().min(src.len());
+                convert::float_to_int(convert::flatten(&src[..n])?, convert::flatten_mut(&mut dst[..n])?, IntFormat::Sc16, 1.0)?;
+                *pos += n;
+                Ok(n)
+            },
+            Chunks::SigMf(reader, pos) => {
+                let src = &reader.ci8()?[*pos..];
+                let n = dst.len().min(src.len());
+                convert::int_to_int(convert::flatten(&src[..n])?, IntFormat::Sc8, convert::flatten_mut(&mut dst[..n])?, IntFormat::Sc16)?;
+                *pos += n;
+                Ok(n)
+            },
+        }
+    }
+
+}
+
+// Reads up to `max` samples of `size` bytes each into `bytes`, stopping early only at the end of the file
+fn read_samples(file:&mut BufReader<File>, bytes:&mut Vec<u8>, max:usize, size:usize) -> Result<usize, &'static str> {
+    bytes.resize(max * size, 0);
+    let mut len = 0;
+    while len < bytes.len() {
+        match file.read(&mut bytes[len..]).map_err(|_| "Unable to read sample file")? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    match (len / size) * size == len {
+        true => Ok(len / size),
+        false => Err("Sample file doesn't hold a whole number of samples"),
+    }
+}
+
+impl Player {
+
+    pub fn from_source(source:Source) -> Self {
+        Self{ source, rate: None, chan: 0, scale: 1.0, repeat: Repeat::Times(1), start_time: None }
+    }
+
+    pub fn from_samples(samples:Vec<(i16, i16)>) -> Self {
+        Self::from_source(Source::Samples(samples))
+    }
+
+    pub fn from_sc16_file<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let source = Source::Sc16File(path.as_ref().to_path_buf());
+        source.num_samples()?;
+        Ok(Self::from_source(source))
+    }
+
+    pub fn from_fc32_file<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let source = Source::Fc32File(path.as_ref().to_path_buf());
+        source.num_samples()?;
+        Ok(Self::from_source(source))
+    }
+
+    pub fn from_sigmf<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let reader = SigMfReader::open(&path)?;
+        if reader.num_channels() != 1 {
+            return Err("Only single-channel SigMF recordings can be played");
+        }
+        Ok(Self{ rate: reader.sample_rate(), ..Self::from_source(Source::SigMf(path.as_ref().to_path_buf())) })
+    }
+
+    pub fn chan(mut self, chan:usize) -> Self { self.chan = chan; self }
+    pub fn scale(mut self, scale:f64) -> Self { self.scale = scale; self }
+    pub fn repeat(mut self, repeat:Repeat) -> Self { self.repeat = repeat; self }
+    pub fn start_time(mut self, time_spec:TimeSpec) -> Self { self.start_time = Some(time_spec); self }
+
+    // Applies the amplitude scale in place, saturating rather than wrapping
+    pub fn apply_scale(&self, samples:&mut [(i16, i16)]) {
+        if self.scale == 1.0 {
+            return;
+        }
+        let scale = |x:i16| (x as f64 * self.scale).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+        for (i, q) in samples.iter_mut() {
+            (*i, *q) = (scale(*i), scale(*q));
+        }
+    }
+
+    // Plays until the requested number of passes is done or `stop` is set, which is checked before every packet
+    pub fn run(&self, usrp:&mut USRP, stop:&AtomicBool) -> Result<PlaybackReport, &'static str> {
+        if self.source.num_samples()? == 0 {
+            return Err("Nothing to play");
+        }
+        if self.repeat == Repeat::Times(0) {
+            return Err("Player needs to play at least once");
+        }
+        if let Some(rate) = self.rate {
+            if (usrp.get_tx_rate(self.chan)? - rate).abs() > rate * 1e-6 {
+                return Err("Recording sample rate doesn't match the TX rate");
+            }
+        }
+
+        let mut streamer = usrp.tx_stream(&StreamArgsBuilder::new().channels(&[self.chan]))?;
+        let mut buffer = vec![(0, 0); streamer.get_max_num_samps()?];
+        if let Some(start) = self.start_time {
+            streamer.set_timeout(time_diff(start, usrp.get_time_now(0)?).max(0.0) + DEFAULT_TIMEOUT);
+        }
+
+        let mut report = PlaybackReport::default();
+        let mut md = AsyncMetadata::new()?;
+        'passes: loop {
+            let mut chunks = self.source.chunks()?;
+            loop {
+                if stop.load(Ordering::Relaxed) {
+                    break 'passes;
+                }
+                let n = chunks.read(&mut buffer)?;
+                if n == 0 {
+                    break;
+                }
+                self.apply_scale(&mut buffer[..n]);
+                let sent = match report.samples_sent {
+                    0 => streamer.start_coherent(&buffer[..n], self.start_time)?,
+                    _ => streamer.continue_coherent(&buffer[..n])?,
+                };
+                streamer.set_timeout(DEFAULT_TIMEOUT);
+                report.samples_sent += sent;
+                poll_async(&mut streamer, &mut md, &mut report, 0.0)?;
+            }
+            report.passes += 1;
+
+            if let Repeat::Times(n) = self.repeat {
+                if report.passes >= n {
+                    break;
+                }
+            }
+        }
+
+        // Nothing went out if `stop` was already set, so there's no burst to end
+        if report.samples_sent == 0 {
+            return Ok(report);
+        }
+        streamer.complete_coherent(&[])?;
+
+        let deadline = Instant::now() + BURST_ACK_TIMEOUT;
+        while !report.burst_acked && Instant::now() < deadline {
+            poll_async(&mut streamer, &mut md, &mut report, 0.1)?;
+        }
+        Ok(report)
+    }
+
+}
+
+// Drains whatever async messages are waiting, blocking up to `timeout` for the first one
+fn poll_async(streamer:&mut TxStreamer, md:&mut AsyncMetadata, report:&mut PlaybackReport, timeout:f64) -> Result<(), &'static str> {
+    let mut timeout = timeout;
+    while streamer.recv_async_msg(md, timeout)? {
+        report.record(md.event_code()?);
+        timeout = 0.0;
+    }
+    Ok(())
+}
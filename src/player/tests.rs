@@ -0,0 +1,94 @@
+use crate::io;
+use crate::io::sigmf::{self, Global, SigMfWriter};
+use crate::io::transcode::{self, IqFormat};
+use crate::player::{PlaybackReport, Player, Source};
+use crate::test_util::TempDir;
+use crate::types::metadata::AsyncMetadataEventCode;
+
+// Reads a whole pass through a buffer small enough that the source is split across reads
+fn read_all(source:&Source) -> Vec<(i16, i16)> {
+    let mut chunks = source.chunks().unwrap();
+    let mut buff = [(0, 0); 3];
+    let mut ans = vec![];
+    loop {
+        match chunks.read(&mut buff).unwrap() {
+            0 => return ans,
+            n => ans.extend_from_slice(&buff[..n]),
+        }
+    }
+}
+
+#[test]
+fn load_raw_files() {
+    let dir = TempDir::new("player");
+    let sc16_path = dir.join("player.sc16");
+    std::fs::write(&sc16_path, [0x01, 0x00, 0xFF, 0xFF, 0x00, 0x80, 0xFF, 0x7F]).unwrap();
+    assert_eq!(Ok(2), Player::from_sc16_file(&sc16_path).unwrap().source.num_samples());
+    let player = Player::from_sc16_file(&sc16_path).unwrap();
+    assert_eq!(vec![(1, -1), (i16::MIN, i16::MAX)], read_all(&player.source));
+
+    let fc32_path = dir.join("player.fc32");
+    let bytes:Vec<u8> = [0.5f32, -1.0, 2.0, 0.0].iter().flat_map(|x| x.to_le_bytes().to_vec()).collect();
+    std::fs::write(&fc32_path, bytes).unwrap();
+    let player = Player::from_fc32_file(&fc32_path).unwrap();
+    assert_eq!(vec![(16384, -32767), (32767, 0)], read_all(&player.source));
+
+    std::fs::write(dir.join("short.sc16"), [0x01, 0x00, 0xFF]).unwrap();
+    assert!(Player::from_sc16_file(dir.join("short.sc16")).is_err());
+}
+
+#[test]
+fn load_sigmf_in_chunks() {
+    let dir = TempDir::new("player_sigmf");
+    let samples:Vec<(i16, i16)> = (0..10).map(|n| (n * 100, -n)).collect();
+    let mut writer = SigMfWriter::create(dir.join("a"), Global::ci16(1e6, 1)).unwrap();
+    writer.write_sc16(&samples).unwrap();
+    writer.finish().unwrap();
+
+    let player = Player::from_sigmf(dir.join("a")).unwrap();
+    assert_eq!(Some(1e6), player.rate);
+    assert_eq!(samples, read_all(&player.source));
+
+    let mut writer = SigMfWriter::create(dir.join("b"), Global::cf32(1e6, 1)).unwrap();
+    writer.write_cf32(&[(0.5, -1.0), (2.0, 0.0)]).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(vec![(16384, -32767), (32767, 0)], read_all(&Player::from_sigmf(dir.join("b")).unwrap().source));
+}
+
+#[test]
+fn ci8_plays_and_converts_at_the_same_level() {
+    let dir = TempDir::new("player_ci8");
+    let mut writer = SigMfWriter::create(dir.join("a"), Global::ci16(1e6, 1)).unwrap();
+    writer.meta_mut().global.datatype = "ci8".to_owned();
+    writer.finish().unwrap();
+    std::fs::write(sigmf::paths(dir.join("a")).0, [0x01, 0xFF, 0x7F, 0x80]).unwrap();
+
+    let expected = vec![(1 << 8, -1 << 8), (127 << 8, -128 << 8)];
+    assert_eq!(expected, read_all(&Player::from_sigmf(dir.join("a")).unwrap().source));
+
+    transcode::transcode(dir.join("a"), IqFormat::SigMf, dir.join("out.sc16"), IqFormat::Sc16, None).unwrap();
+    assert_eq!(crate::convert::iq_to_le_bytes(&expected), std::fs::read(dir.join("out.sc16")).unwrap());
+}
+
+#[test]
+fn scaling_saturates() {
+    let mut samples = vec![(100, -100), (30000, -30000)];
+    Player::from_samples(vec![]).scale(2.0).apply_scale(&mut samples);
+    assert_eq!(vec![(200, -200), (i16::MAX, i16::MIN)], samples);
+    assert_eq!(-32767, io::fc32_to_sc16(-1.0));
+}
+
+#[test]
+fn async_events() {
+    let mut report = PlaybackReport::default();
+    for event in [AsyncMetadataEventCode::Underflow, AsyncMetadataEventCode::UnderflowInPacket, AsyncMetadataEventCode::SeqError] {
+        report.record(event);
+    }
+    assert_eq!(2, report.underflows);
+    assert_eq!(1, report.seq_errors);
+    assert!(!report.is_clean());
+
+    let mut report = PlaybackReport::default();
+    report.record(AsyncMetadataEventCode::BurstAck);
+    assert!(report.is_clean());
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::lo_sharing::Direction;
+use crate::types::ranges::{MetaRange, Range};
+use crate::usrp::USRP;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MboardProbe {
+    pub mboard:usize,
+    pub name:String,
+    pub master_clock_rate:f64,
+    pub clock_source:String,
+    pub clock_sources:Vec<String>,
+    pub time_source:String,
+    pub time_sources:Vec<String>,
+    pub sensors:Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GainStageProbe {
+    pub name:String,
+    pub range:Vec<Range>,
+}
+
+// Capabilities that not every frontend reports are optional; a device that can't answer shows up as None rather
+// than failing the whole probe
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelProbe {
+    pub chan:usize,
+    pub mboard_id:String,
+    pub mboard_serial:String,
+    pub dboard_id:String,
+    pub dboard_serial:String,
+    pub subdev_name:String,
+    pub subdev_spec:String,
+    pub antenna:String,
+    pub antennas:Vec<String>,
+    pub gain_range:Vec<Range>,
+    pub gain_stages:Vec<GainStageProbe>,
+    pub freq_range:Vec<Range>,
+    pub fe_freq_range:Option<Vec<Range>>,
+    pub rate_range:Vec<Range>,
+    pub bandwidth_range:Option<Vec<Range>>,
+    pub lo_names:Option<Vec<String>>,
+    pub sensors:Vec<String>,
+}
+
+// Everything the device reports about what it can do, in a form that can be archived alongside recorded data
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeviceProbe {
+    pub mboards:Vec<MboardProbe>,
+    pub rx:Vec<ChannelProbe>,
+    pub tx:Vec<ChannelProbe>,
+}
+
+impl MboardProbe {
+
+    pub fn read(usrp:&USRP, mboard:usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            mboard,
+            name: usrp.get_mboard_name(mboard)?,
+            master_clock_rate: usrp.get_master_clock_rate(mboard)?,
+            clock_source: usrp.get_clock_source(mboard)?,
+            clock_sources: usrp.get_clock_sources(mboard)?,
+            time_source: usrp.get_time_source(mboard)?,
+            time_sources: usrp.get_time_sources(mboard)?,
+            sensors: usrp.get_mboard_sensor_names(mboard)?,
+        })
+    }
+
+}
+
+impl ChannelProbe {
+
+    pub fn read(usrp:&USRP, direction:Direction, chan:usize) -> Result<Self, &'static str> {
+        let ranges = |range:Result<MetaRange, &'static str>| range.and_then(|r| r.get_rust_vec());
+
+        let (info, antennas, gain_names, lo_names, sensors) = match direction {
+            Direction::Rx => (
+                usrp.get_rx_info(chan)?, usrp.get_rx_antennas(chan)?, usrp.get_rx_gain_names(chan)?,
+                usrp.get_rx_lo_names(chan).and_then(|sv| sv.get_rust_vec()).ok(), usrp.get_rx_sensor_names(chan)?
+            ),
+            Direction::Tx => (
+                usrp.get_tx_info(chan)?, usrp.get_tx_antennas(chan)?, usrp.get_tx_gain_names(chan)?,
+                usrp.get_tx_lo_names(chan).and_then(|sv| sv.get_rust_vec()).ok(), usrp.get_tx_sensor_names(chan)?
+            ),
+        };
+
+        let gain_range = |name:&str| match direction {
+            Direction::Rx => ranges(usrp.get_rx_gain_range(chan, name)),
+            Direction::Tx => ranges(usrp.get_tx_gain_range(chan, name)),
+        };
+        let mut gain_stages = vec![];
+        for name in gain_names {
+            gain_stages.push(GainStageProbe{ range: gain_range(&name)?, name });
+        }
+
+        let (freq_range, fe_freq_range, rate_range, bandwidth_range) = match direction {
+            Direction::Rx => (
+                ranges(usrp.get_rx_freq_range(chan))?, ranges(usrp.get_fe_rx_freq_range(chan)).ok(),
+                ranges(usrp.get_rx_rates(chan))?, ranges(usrp.get_rx_bandwidth_range(chan)).ok()
+            ),
+            Direction::Tx => (
+                ranges(usrp.get_tx_freq_range(chan))?, ranges(usrp.get_fe_tx_freq_range(chan)).ok(),
+                ranges(usrp.get_tx_rates(chan))?, ranges(usrp.get_tx_bandwidth_range(chan)).ok()
+            ),
+        };
+
+        Ok(Self {
+            chan,
+            mboard_id: info.mboard_id()?,
+            mboard_serial: info.mboard_serial()?,
+            dboard_id: info.id()?,
+            dboard_serial: info.serial()?,
+            subdev_name: info.subdev_name()?,
+            subdev_spec: info.subdev_spec()?,
+            antenna: info.antenna()?,
+            antennas,
+            gain_range: gain_range("")?,
+            gain_stages,
+            freq_range, fe_freq_range, rate_range, bandwidth_range,
+            lo_names,
+            sensors,
+        })
+    }
+
+}
+
+impl DeviceProbe {
+
+    pub fn read(usrp:&USRP) -> Result<Self, &'static str> {
+        let mut ans = Self{ mboards: vec![], rx: vec![], tx: vec![] };
+        for mboard in 0..usrp.num_mboards()? {
+            ans.mboards.push(MboardProbe::read(usrp, mboard)?);
+        }
+        for chan in 0..usrp.rx_num_channels()? {
+            ans.rx.push(ChannelProbe::read(usrp, Direction::Rx, chan)?);
+        }
+        for chan in 0..usrp.tx_num_channels()? {
+            ans.tx.push(ChannelProbe::read(usrp, Direction::Tx, chan)?);
+        }
+        Ok(ans)
+    }
+
+    pub fn to_json(&self) -> Result<String, &'static str> {
+        serde_json::to_string_pretty(self).map_err(|_| "Unable to serialize device probe to JSON")
+    }
+
+    pub fn from_json(s:&str) -> Result<Self, &'static str> {
+        serde_json::from_str(s).map_err(|_| "Unable to parse device probe from JSON")
+    }
+
+    pub fn to_toml(&self) -> Result<String, &'static str> {
+        toml::to_string_pretty(self).map_err(|_| "Unable to serialize device probe to TOML")
+    }
+
+    pub fn from_toml(s:&str) -> Result<Self, &'static str> {
+        toml::from_str(s).map_err(|_| "Unable to parse device probe from TOML")
+    }
+
+    // Paths (like `rx[1].antennas`) of every field that differs between two probes, e.g. two units of the same model
+    pub fn diff(&self, other:&DeviceProbe) -> Result<Vec<String>, &'static str> {
+        let a = serde_json::to_value(self).map_err(|_| "Unable to serialize device probe")?;
+        let b = serde_json::to_value(other).map_err(|_| "Unable to serialize device probe")?;
+        let mut ans = vec![];
+        diff_values("", &a, &b, &mut ans);
+        Ok(ans)
+    }
+
+}
+
+pub(crate) fn diff_values(path:&str, a:&Value, b:&Value, out:&mut Vec<String>) {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, a_val) in a.iter() {
+                let path = if path.is_empty() { key.to_owned() } else { format!("{}.{}", path, key) };
+                diff_values(&path, a_val, b.get(key).unwrap_or(&Value::Null), out);
+            }
+            for key in b.keys().filter(|k| !a.contains_key(*k)) {
+                out.push(if path.is_empty() { key.to_owned() } else { format!("{}.{}", path, key) });
+            }
+        },
+        // Lists of structs with the same length are compared element by element; anything else is compared whole
+        (Value::Array(a_items), Value::Array(b_items)) if a_items.len() == b_items.len() && a_items.iter().all(|v| v.is_object()) => {
+            for (idx, (a_val, b_val)) in a_items.iter().zip(b_items).enumerate() {
+                diff_values(&format!("{}[{}]", path, idx), a_val, b_val, out);
+            }
+        },
+        (a, b) if a != b => out.push(path.to_owned()),
+        _ => (),
+    }
+}
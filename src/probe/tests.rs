@@ -0,0 +1,76 @@
+use crate::probe::{ChannelProbe, DeviceProbe, GainStageProbe, MboardProbe};
+use crate::types::ranges::Range;
+
+fn channel(chan:usize, antenna:&str) -> ChannelProbe {
+    ChannelProbe {
+        chan,
+        mboard_id: "B210".to_owned(),
+        mboard_serial: "31A5B1C".to_owned(),
+        dboard_id: "B210".to_owned(),
+        dboard_serial: "".to_owned(),
+        subdev_name: "FE-RX1".to_owned(),
+        subdev_spec: "A:A".to_owned(),
+        antenna: antenna.to_owned(),
+        antennas: vec!["TX/RX".to_owned(), "RX2".to_owned()],
+        gain_range: vec![Range::new(0.0, 76.0, 1.0)],
+        gain_stages: vec![GainStageProbe{ name: "PGA".to_owned(), range: vec![Range::new(0.0, 76.0, 1.0)] }],
+        freq_range: vec![Range::new(50.0e6, 6.0e9, 0.0)],
+        fe_freq_range: Some(vec![Range::new(50.0e6, 6.0e9, 0.0)]),
+        rate_range: vec![Range::new(62.5e3, 61.44e6, 0.0)],
+        bandwidth_range: None,
+        lo_names: Some(vec![]),
+        sensors: vec!["lo_locked".to_owned(), "rssi".to_owned()],
+    }
+}
+
+fn probe() -> DeviceProbe {
+    DeviceProbe {
+        mboards: vec![MboardProbe {
+            mboard: 0,
+            name: "B210".to_owned(),
+            master_clock_rate: 16.0e6,
+            clock_source: "internal".to_owned(),
+            clock_sources: vec!["internal".to_owned(), "external".to_owned(), "gpsdo".to_owned()],
+            time_source: "none".to_owned(),
+            time_sources: vec!["none".to_owned(), "internal".to_owned(), "external".to_owned()],
+            sensors: vec!["ref_locked".to_owned()],
+        }],
+        rx: vec![channel(0, "RX2"), channel(1, "RX2")],
+        tx: vec![channel(0, "TX/RX")],
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    let probe = probe();
+    assert_eq!(probe, DeviceProbe::from_json(&probe.to_json().unwrap()).unwrap());
+}
+
+#[test]
+fn toml_round_trip() {
+    let probe = probe();
+    let toml = probe.to_toml().unwrap();
+    assert!(toml.contains("[[rx]]"));
+    assert_eq!(probe, DeviceProbe::from_toml(&toml).unwrap());
+}
+
+#[test]
+fn diff_between_units() {
+    let a = probe();
+    assert!(a.diff(&a).unwrap().is_empty());
+
+    let mut b = probe();
+    b.mboards[0].master_clock_rate = 32.0e6;
+    b.rx[1].antenna = "TX/RX".to_owned();
+    b.rx[1].freq_range[0].stop = 3.8e9;
+    b.tx[0].bandwidth_range = Some(vec![Range::new(200.0e3, 56.0e6, 0.0)]);
+    b.tx[0].antennas.pop();
+
+    assert_eq!(a.diff(&b).unwrap(), vec![
+        "mboards[0].master_clock_rate",
+        "rx[1].antenna",
+        "rx[1].freq_range[0].stop",
+        "tx[0].antennas",
+        "tx[0].bandwidth_range",
+    ]);
+}
@@ -0,0 +1,369 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lo_sharing::Direction;
+use crate::probe::diff_values;
+use crate::types::tune_request::TuneRequestBuilder;
+use crate::usrp::USRP;
+use crate::usrp::subdev_spec::SubdevSpec;
+
+#[cfg(test)]
+mod tests;
+
+// Every setting is optional so a profile only has to mention what it cares about; anything left out is not
+// touched by `apply`
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoConfig {
+    pub name:String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source:Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export:Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freq:Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelConfig {
+    pub chan:usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate:Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freq:Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lo_offset:Option<f64>,      // Can't be read back, so snapshots never include it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gain:Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub antenna:Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth:Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lo:Vec<LoConfig>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MboardConfig {
+    pub mboard:usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_clock_rate:Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_source:Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_source:Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_subdev_spec:Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_subdev_spec:Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RadioConfig {
+    pub mboards:Vec<MboardConfig>,
+    pub rx:Vec<ChannelConfig>,
+    pub tx:Vec<ChannelConfig>,
+}
+
+impl LoConfig {
+
+    fn read(usrp:&USRP, direction:Direction, chan:usize, name:&str) -> Self {
+        let (source, export, freq) = match direction {
+            Direction::Rx => (usrp.get_rx_lo_source(name, chan), usrp.get_rx_lo_export_enabled(name, chan), usrp.get_rx_lo_freq(name, chan)),
+            Direction::Tx => (usrp.get_tx_lo_source(name, chan), usrp.get_tx_lo_export_enabled(name, chan), usrp.get_tx_lo_freq(name, chan)),
+        };
+        Self{ name: name.to_owned(), source: source.ok(), export: export.ok(), freq: freq.ok() }
+    }
+
+    // Source and export need to be in place before the channel is tuned
+    fn apply_routing(&self, usrp:&USRP, direction:Direction, chan:usize) -> Result<(), &'static str> {
+        if let Some(source) = &self.source {
+            match direction {
+                Direction::Rx => usrp.set_rx_lo_source(source, &self.name, chan)?,
+                Direction::Tx => usrp.set_tx_lo_source(source, &self.name, chan)?,
+            }
+        }
+        if let Some(export) = self.export {
+            match direction {
+                Direction::Rx => usrp.set_rx_lo_export_enabled(export, &self.name, chan)?,
+                Direction::Tx => usrp.set_tx_lo_export_enabled(export, &self.name, chan)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_freq(&self, usrp:&mut USRP, direction:Direction, chan:usize) -> Result<(), &'static str> {
+        if let Some(freq) = self.freq {
+            match direction {
+                Direction::Rx => usrp.set_rx_lo_freq(freq, &self.name, chan)?,
+                Direction::Tx => usrp.set_tx_lo_freq(freq, &self.name, chan)?,
+            };
+        }
+        Ok(())
+    }
+
+    fn mask(&self, template:&Self) -> Self {
+        Self {
+            name: self.name.clone(),
+            source: template.source.as_ref().and(self.source.clone()),
+            export: template.export.and(self.export),
+            freq: template.freq.and(self.freq),
+        }
+    }
+
+}
+
+impl ChannelConfig {
+
+    pub fn new(chan:usize) -> Self {
+        Self{ chan, ..Self::default() }
+    }
+
+    // Everything that can be read back from the device; LOs that can't report a setting are left as None
+    pub fn read(usrp:&USRP, direction:Direction, chan:usize) -> Result<Self, &'static str> {
+        let lo_names = match direction {
+            Direction::Rx => usrp.get_rx_lo_names(chan),
+            Direction::Tx => usrp.get_tx_lo_names(chan),
+        }.and_then(|sv| sv.get_rust_vec()).unwrap_or_default();
+        let lo = lo_names.iter().map(|name| LoConfig::read(usrp, direction, chan, name)).collect();
+
+        Ok(match direction {
+            Direction::Rx => Self {
+                chan, lo_offset: None, lo,
+                rate: Some(usrp.get_rx_rate(chan)?),
+                freq: Some(usrp.get_rx_freq(chan)?),
+                gain: Some(usrp.get_rx_gain(chan, "")?),
+                antenna: Some(usrp.get_rx_antenna(chan)?),
+                bandwidth: usrp.get_rx_bandwidth(chan).ok(),
+            },
+            Direction::Tx => Self {
+                chan, lo_offset: None, lo,
+                rate: Some(usrp.get_tx_rate(chan)?),
+                freq: Some(usrp.get_tx_freq(chan)?),
+                gain: Some(usrp.get_tx_gain(chan, "")?),
+                antenna: Some(usrp.get_tx_antenna(chan)?),
+                bandwidth: usrp.get_tx_bandwidth(chan).ok(),
+            },
+        })
+    }
+
+    // Settings are applied in the same order UHD's own examples use: the frontend first, then the rate, then the
+    // tune (which depends on the rate for the DSP part), and the gain last since some frontends coerce it per band.
+    // A manual LO frequency goes right after the tune since the tune would otherwise override it.
+    pub fn apply(&self, usrp:&mut USRP, direction:Direction) -> Result<(), &'static str> {
+        let chan = self.chan;
+
+        if let Some(antenna) = &self.antenna {
+            match direction {
+                Direction::Rx => usrp.set_rx_antenna(antenna, chan)?,
+                Direction::Tx => usrp.set_tx_antenna(antenna, chan)?,
+            }
+        }
+        if let Some(rate) = self.rate {
+            match direction {
+                Direction::Rx => usrp.set_rx_rate(rate, chan)?,
+                Direction::Tx => usrp.set_tx_rate(rate, chan)?,
+            }
+        }
+        if let Some(bandwidth) = self.bandwidth {
+            match direction {
+                Direction::Rx => usrp.set_rx_bandwidth(bandwidth, chan)?,
+                Direction::Tx => usrp.set_tx_bandwidth(bandwidth, chan)?,
+            }
+        }
+        for lo in self.lo.iter() {
+            lo.apply_routing(usrp, direction, chan)?;
+        }
+        if let Some(freq) = self.freq {
+            let request = match self.lo_offset {
+                Some(offset) => TuneRequestBuilder::new(freq).lo_offset(offset),
+                None => TuneRequestBuilder::new(freq),
+            };
+            match direction {
+                Direction::Rx => usrp.tune_rx(&request, chan)?,
+                Direction::Tx => usrp.tune_tx(&request, chan)?,
+            };
+        }
+        for lo in self.lo.iter() {
+            lo.apply_freq(usrp, direction, chan)?;
+        }
+        if let Some(gain) = self.gain {
+            match direction {
+                Direction::Rx => usrp.set_rx_gain(gain, chan, "")?,
+                Direction::Tx => usrp.set_tx_gain(gain, chan, "")?,
+            }
+        }
+
+        Ok(())
+    }
+
+    // The settings in this config as they currently are on the device.  LOs are read by the names given here, which
+    // may include names like `all` that don't show up in the device's list of LO names.
+    fn read_back(&self, usrp:&USRP, direction:Direction) -> Result<Self, &'static str> {
+        let mut actual = Self::read(usrp, direction, self.chan)?;
+        actual.lo_offset = self.lo_offset;
+        actual.lo = self.lo.iter().map(|lo| LoConfig::read(usrp, direction, self.chan, &lo.name)).collect();
+        Ok(actual.mask(self))
+    }
+
+    // Keeps only the settings that are present in `template`
+    pub fn mask(&self, template:&Self) -> Self {
+        let lo = template.lo.iter()
+            .filter_map(|t| self.lo.iter().find(|lo| lo.name == t.name).map(|lo| lo.mask(t)))
+            .collect();
+        Self {
+            chan: self.chan,
+            rate: template.rate.and(self.rate),
+            freq: template.freq.and(self.freq),
+            lo_offset: template.lo_offset.and(self.lo_offset),
+            gain: template.gain.and(self.gain),
+            antenna: template.antenna.as_ref().and(self.antenna.clone()),
+            bandwidth: template.bandwidth.and(self.bandwidth),
+            lo,
+        }
+    }
+
+}
+
+impl MboardConfig {
+
+    pub fn new(mboard:usize) -> Self {
+        Self{ mboard, ..Self::default() }
+    }
+
+    pub fn read(usrp:&USRP, mboard:usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            mboard,
+            master_clock_rate: Some(usrp.get_master_clock_rate(mboard)?),
+            clock_source: Some(usrp.get_clock_source(mboard)?),
+            time_source: Some(usrp.get_time_source(mboard)?),
+            rx_subdev_spec: Some(usrp.get_subdev_spec(mboard)?.to_string()?),
+            tx_subdev_spec: Some(usrp.get_tx_subdev_spec(mboard)?.to_string()?),
+        })
+    }
+
+    // The master clock rate and subdev specs change the channel mapping and the available rates, so they go first
+    pub fn apply(&self, usrp:&mut USRP) -> Result<(), &'static str> {
+        let mboard = self.mboard;
+        if let Some(rate) = self.master_clock_rate {
+            usrp.set_master_clock_rate(rate, mboard)?;
+        }
+        if let Some(spec) = &self.rx_subdev_spec {
+            usrp.set_rx_subdev_spec(&SubdevSpec::new(spec)?, mboard)?;
+        }
+        if let Some(spec) = &self.tx_subdev_spec {
+            usrp.set_tx_subdev_spec(&SubdevSpec::new(spec)?, mboard)?;
+        }
+        if let Some(source) = &self.clock_source {
+            usrp.set_clock_source(source, mboard)?;
+        }
+        if let Some(source) = &self.time_source {
+            usrp.set_time_source(source, mboard)?;
+        }
+        Ok(())
+    }
+
+    pub fn mask(&self, template:&Self) -> Self {
+        Self {
+            mboard: self.mboard,
+            master_clock_rate: template.master_clock_rate.and(self.master_clock_rate),
+            clock_source: template.clock_source.as_ref().and(self.clock_source.clone()),
+            time_source: template.time_source.as_ref().and(self.time_source.clone()),
+            rx_subdev_spec: template.rx_subdev_spec.as_ref().and(self.rx_subdev_spec.clone()),
+            tx_subdev_spec: template.tx_subdev_spec.as_ref().and(self.tx_subdev_spec.clone()),
+        }
+    }
+
+}
+
+impl RadioConfig {
+
+    // Current state of every mboard and channel; applying a snapshot restores it
+    pub fn snapshot(usrp:&USRP) -> Result<Self, &'static str> {
+        let mut ans = Self::default();
+        for mboard in 0..usrp.num_mboards()? {
+            ans.mboards.push(MboardConfig::read(usrp, mboard)?);
+        }
+        for chan in 0..usrp.rx_num_channels()? {
+            ans.rx.push(ChannelConfig::read(usrp, Direction::Rx, chan)?);
+        }
+        for chan in 0..usrp.tx_num_channels()? {
+            ans.tx.push(ChannelConfig::read(usrp, Direction::Tx, chan)?);
+        }
+        Ok(ans)
+    }
+
+    // Applies the config and returns the same settings as read back from the device, which shows anything UHD
+    // coerced (rates, frequencies and gains snapped to what the hardware can do)
+    pub fn apply(&self, usrp:&mut USRP) -> Result<RadioConfig, &'static str> {
+        for mboard in self.mboards.iter() {
+            mboard.apply(usrp)?;
+        }
+        for chan in self.rx.iter() {
+            chan.apply(usrp, Direction::Rx)?;
+        }
+        for chan in self.tx.iter() {
+            chan.apply(usrp, Direction::Tx)?;
+        }
+
+        let mut ans = Self::default();
+        for mboard in self.mboards.iter() {
+            ans.mboards.push(MboardConfig::read(usrp, mboard.mboard)?.mask(mboard));
+        }
+        for chan in self.rx.iter() {
+            ans.rx.push(chan.read_back(usrp, Direction::Rx)?);
+        }
+        for chan in self.tx.iter() {
+            ans.tx.push(chan.read_back(usrp, Direction::Tx)?);
+        }
+        Ok(ans)
+    }
+
+    // Paths (like `rx[0].gain`) of every setting that differs between two configs
+    pub fn diff(&self, other:&RadioConfig) -> Result<Vec<String>, &'static str> {
+        let a = serde_json::to_value(self).map_err(|_| "Unable to serialize radio config")?;
+        let b = serde_json::to_value(other).map_err(|_| "Unable to serialize radio config")?;
+        let mut ans = vec![];
+        diff_values("", &a, &b, &mut ans);
+        Ok(ans)
+    }
+
+    pub fn to_json(&self) -> Result<String, &'static str> {
+        serde_json::to_string_pretty(self).map_err(|_| "Unable to serialize radio config to JSON")
+    }
+
+    pub fn from_json(s:&str) -> Result<Self, &'static str> {
+        serde_json::from_str(s).map_err(|_| "Unable to parse radio config from JSON")
+    }
+
+    pub fn to_toml(&self) -> Result<String, &'static str> {
+        toml::to_string_pretty(self).map_err(|_| "Unable to serialize radio config to TOML")
+    }
+
+    pub fn from_toml(s:&str) -> Result<Self, &'static str> {
+        toml::from_str(s).map_err(|_| "Unable to parse radio config from TOML")
+    }
+
+    // Format is picked from the extension; anything other than `.json` is read as TOML
+    pub fn load<P: AsRef<Path>>(path:P) -> Result<Self, &'static str> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|_| "Unable to read radio config file")?;
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            _ => Self::from_toml(&contents),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path:P) -> Result<(), &'static str> {
+        let contents = match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("json") => self.to_json()?,
+            _ => self.to_toml()?,
+        };
+        std::fs::write(path, contents).map_err(|_| "Unable to write radio config file")
+    }
+
+}
@@ -0,0 +1,70 @@
+use crate::radio_config::{ChannelConfig, LoConfig, MboardConfig, RadioConfig};
+
+const PROFILE:&str = r#"
+[[mboards]]
+mboard = 0
+clock_source = "external"
+rx_subdev_spec = "A:0 B:0"
+
+[[rx]]
+chan = 0
+rate = 1.0e6
+freq = 915.0e6
+lo_offset = 2.0e6
+gain = 30.0
+antenna = "RX2"
+
+[[rx]]
+chan = 1
+freq = 915.0e6
+
+[[rx.lo]]
+name = "all"
+source = "companion"
+"#;
+
+#[test]
+fn load_partial_profile() {
+    let config = RadioConfig::from_toml(PROFILE).unwrap();
+    assert_eq!(Some("external".to_owned()), config.mboards[0].clock_source);
+    assert_eq!(None, config.mboards[0].time_source);
+    assert_eq!(Some(2.0e6), config.rx[0].lo_offset);
+    assert_eq!(None, config.rx[1].rate);
+    assert_eq!(vec![LoConfig{ name: "all".to_owned(), source: Some("companion".to_owned()), export: None, freq: None }], config.rx[1].lo);
+    assert!(config.tx.is_empty());
+}
+
+#[test]
+fn round_trips() {
+    let config = RadioConfig::from_toml(PROFILE).unwrap();
+    assert_eq!(config, RadioConfig::from_toml(&config.to_toml().unwrap()).unwrap());
+    assert_eq!(config, RadioConfig::from_json(&config.to_json().unwrap()).unwrap());
+
+    // Settings that weren't given don't show up in the output at all
+    assert!(!config.to_json().unwrap().contains("null"));
+}
+
+#[test]
+fn mask_keeps_requested_settings() {
+    let actual = ChannelConfig {
+        chan: 0, rate: Some(1.0e6), freq: Some(915.0e6), lo_offset: None, gain: Some(29.5),
+        antenna: Some("RX2".to_owned()), bandwidth: Some(56.0e6), lo: vec![],
+    };
+    let requested = ChannelConfig{ gain: Some(30.0), freq: Some(915.0e6), ..ChannelConfig::new(0) };
+    assert_eq!(ChannelConfig{ gain: Some(29.5), freq: Some(915.0e6), ..ChannelConfig::new(0) }, actual.mask(&requested));
+
+    let actual = MboardConfig{ clock_source: Some("internal".to_owned()), master_clock_rate: Some(32.0e6), ..MboardConfig::new(1) };
+    let requested = MboardConfig{ clock_source: Some("external".to_owned()), ..MboardConfig::new(1) };
+    assert_eq!(MboardConfig{ clock_source: Some("internal".to_owned()), ..MboardConfig::new(1) }, actual.mask(&requested));
+}
+
+#[test]
+fn diff_requested_and_coerced() {
+    let requested = RadioConfig::from_toml(PROFILE).unwrap();
+    let mut coerced = requested.clone();
+    assert!(requested.diff(&coerced).unwrap().is_empty());
+
+    coerced.rx[0].gain = Some(29.5);
+    coerced.rx[1].lo[0].source = Some("internal".to_owned());
+    assert_eq!(requested.diff(&coerced).unwrap(), vec!["rx[0].gain", "rx[1].lo[0].source"]);
+}
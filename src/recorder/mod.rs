@@ -0,0 +1,423 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::io::sigmf::{self, Annotation, Capture, Global, SigMfWriter};
+use crate::lo_sharing::Direction;
+use crate::radio_config::ChannelConfig;
+use crate::timing::time_sync::time_add;
+use crate::types::TimeSpec;
+use crate::types::metadata::RxMetadataErrorCode;
+use crate::types::stream_args::StreamArgsBuilder;
+use crate::usrp::{StreamCmd, StreamMode, USRP};
+
+#[cfg(test)]
+mod tests;
+
+// Free space is checked whenever a new file is opened and again after this much data has been written
+const SPACE_CHECK_BYTES:u64 = 64 << 20;
+
+// Multi-channel recordings always start on a timed command so every channel shares the first sample time;
+// streaming "now" gives no common start and UHD rejects it for channels on different mboards
+const DEFAULT_MULTI_CHAN_START_DELAY:f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StopReason { Requested, Duration, DiskSpace }
+
+// How a multi-channel recording is split across files
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Layout { FilePerChannel, Interleaved }
+
+// One received packet's worth of samples for every channel, handed from the receive loop to the writer thread
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub samples:Vec<Vec<(i16, i16)>>,
+    pub time_spec:TimeSpec,
+    pub overflow:bool,      // UHD reported an overflow just before this block
+    pub misaligned:bool,    // UHD couldn't line the channels up and dropped samples to realign them
+}
+
+// What a recorded channel was connected to, stored in the metadata so files can be matched back to antennas
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelMapping {
+    pub subdev_spec:String,
+    pub subdev_name:String,
+    pub dboard_serial:String,
+    pub config:ChannelConfig,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileSummary {
+    pub data_path:PathBuf,
+    pub chans:Vec<usize>,
+    pub samples:u64,                // Per channel
+    pub start:Option<TimeSpec>,
+    pub end:Option<TimeSpec>,       // Time just after the last sample
+    pub overflows:usize,
+    pub misalignments:usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordingSummary {
+    pub files:Vec<FileSummary>,
+    pub stop_reason:StopReason,
+}
+
+// Metadata for one output file and the positions in `Block::samples` of the channels that go into it
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Sink {
+    pub global:Global,
+    pub capture:Capture,
+    pub chans:Vec<usize>,
+    pub block_idx:Vec<usize>,
+}
+
+// Streams RX channels to a series of SigMF recordings.  Receiving happens on the calling thread so UHD is drained
+// as fast as possible; a writer thread does the file I/O, rotation and disk-space checks.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    pub dir:PathBuf,
+    pub prefix:String,
+    pub chans:Vec<usize>,
+    pub layout:Layout,
+    pub start_delay:Option<f64>,    // Start every channel together this long after the current device time (see `effective_start_delay`)
+    pub rotate_bytes:Option<u64>,
+    pub rotate_secs:Option<f64>,
+    pub max_secs:Option<f64>,
+    pub min_free_bytes:u64,
+    pub preallocate:bool,
+    pub queue_depth:usize,          // Blocks buffered between the threads before the receive loop has to wait
+}
+
+impl ChannelMapping {
+
+    pub fn read(usrp:&USRP, chan:usize) -> Result<Self, &'static str> {
+        let info = usrp.get_rx_info(chan)?;
+        Ok(Self {
+            subdev_spec: info.subdev_spec()?,
+            subdev_name: usrp.get_rx_subdev_name(chan)?,
+            dboard_serial: info.serial()?,
+            config: ChannelConfig::read(usrp, Direction::Rx, chan)?,
+        })
+    }
+
+}
+
+impl RecordingSummary {
+
+    pub fn samples(&self) -> u64 { self.files.iter().map(|f| f.samples * f.chans.len() as u64).sum() }
+    pub fn overflows(&self) -> usize { self.files.iter().map(|f| f.overflows).sum() }
+    pub fn misalignments(&self) -> usize { self.files.iter().map(|f| f.misalignments).sum() }
+
+}
+
+impl Recorder {
+
+    pub fn new<P: AsRef<Path>>(dir:P, prefix:&str) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            prefix: prefix.to_owned(),
+            chans: vec![0],
+            layout: Layout::FilePerChannel,
+            start_delay: None,
+            rotate_bytes: None, rotate_secs: None, max_secs: None,
+            min_free_bytes: 1 << 30,
+            preallocate: false,
+            queue_depth: 1024,
+        }
+    }
+
+    pub fn chan(mut self, chan:usize) -> Self { self.chans = vec![chan]; self }
+    pub fn chans(mut self, chans:&[usize]) -> Self { self.chans = chans.to_vec(); self }
+    pub fn layout(mut self, layout:Layout) -> Self { self.layout = layout; self }
+    pub fn start_delay(mut self, secs:f64) -> Self { self.start_delay = Some(secs); self }
+    pub fn rotate_bytes(mut self, bytes:u64) -> Self { self.rotate_bytes = Some(bytes); self }
+    pub fn rotate_secs(mut self, secs:f64) -> Self { self.rotate_secs = Some(secs); self }
+    pub fn max_secs(mut self, secs:f64) -> Self { self.max_secs = Some(secs); self }
+    pub fn min_free_bytes(mut self, bytes:u64) -> Self { self.min_free_bytes = bytes; self }
+    pub fn preallocate(mut self, preallocate:bool) -> Self { self.preallocate = preallocate; self }
+    pub fn queue_depth(mut self, depth:usize) -> Self { self.queue_depth = depth; self }
+
+    // Records until `stop` is set, `max_secs` of samples have been written, or the disk is close to full
+    pub fn run(&self, usrp:&mut USRP, stop:&AtomicBool) -> Result<RecordingSummary, &'static str> {
+        if self.chans.is_empty() {
+            return Err("Recorder needs at least one channel");
+        }
+        let mut sinks = self.sinks(usrp)?;
+        let rate = sinks[0].global.sample_rate.ok_or("Unknown sample rate")?;
+        let max_samples = self.max_secs.map(|secs| (secs * rate).round() as u64);
+
+        let mut streamer = usrp.rx_stream(&StreamArgsBuilder::new().channels(&self.chans))?;
+        let spp = streamer.get_max_num_samps()?;
+
+        // A timed start goes into the first capture segment so the writer flags it if the samples start late
+        match self.effective_start_delay() {
+            Some(delay) => {
+                let start = time_add(usrp.get_time_now(0)?, delay);
+                for sink in sinks.iter_mut() {
+                    sink.capture.time_spec = Some(start);
+                }
+                streamer.timeout = streamer.timeout.max(delay + 1.0);
+                streamer.stream(&StreamCmd {
+                    stream_mode: StreamMode::StartContinuous, num_samps: 0, stream_now: false,
+                    time_spec_full_secs: start.0, time_spec_frac_secs: start.1
+                })?;
+            },
+            None => streamer.stream(&StreamCmd::start_continuous_now())?,
+        }
+
+        let (sender, receiver) = sync_channel::<Block>(self.queue_depth.max(1));
+        std::thread::scope(|scope| {
+            let writer = scope.spawn(|| self.write_blocks(receiver, &sinks));
+
+            let mut reason = StopReason::Requested;
+            let mut received:u64 = 0;
+            let mut overflows = streamer.overflow_count();
+            let mut recv_result = Ok(());
+            while !stop.load(Ordering::Relaxed) {
+                if max_samples.map(|max| received >= max).unwrap_or(false) {
+                    reason = StopReason::Duration;
+                    break;
+                }
+
+                let mut samples = vec![vec![(0, 0); spp]; self.chans.len()];
+                let mut buffs:Vec<&mut [(i16, i16)]> = samples.iter_mut().map(|s| &mut s[..]).collect();
+                let (n, time_spec) = match streamer.recv_one_multi_chan(&mut buffs) {
+                    Ok(ans) => ans,
+                    Err(e) => { recv_result = Err(e); break; }
+                };
+
+                let misaligned = match streamer.rx_metadata_error_code() {
+                    Ok(RxMetadataErrorCode::Alignment) => true,
+                    Ok(_) => match streamer.rx_metadata_ok() {
+                        Ok(()) => false,
+                        Err(e) => { recv_result = Err(e); break; }
+                    },
+                    Err(e) => { recv_result = Err(e); break; }
+                };
+
+                let overflow = streamer.overflow_count() != overflows;
+                overflows = streamer.overflow_count();
+                if n == 0 && !overflow && !misaligned {
+                    continue;
+                }
+
+                let keep = max_samples.map(|max| (max - received).min(n as u64) as usize).unwrap_or(n);
+                for chan_samples in samples.iter_mut() {
+                    chan_samples.truncate(keep);
+                }
+                received += keep as u64;
+
+                // The writer only hangs up when it has decided to stop
+                if sender.send(Block{ samples, time_spec, overflow, misaligned }).is_err() {
+                    break;
+                }
+            }
+
+            drop(sender);
+            let stop_result = streamer.stream(&StreamCmd::stop_continuous_now());
+            let (files, writer_reason) = writer.join().map_err(|_| "Recorder writer thread panicked")??;
+            recv_result?;
+            stop_result?;
+            Ok(RecordingSummary{ files, stop_reason: writer_reason.unwrap_or(reason) })
+        })
+    }
+
+    pub fn effective_start_delay(&self) -> Option<f64> {
+        match (self.start_delay, self.chans.len()) {
+            (None, n) if n > 1 => Some(DEFAULT_MULTI_CHAN_START_DELAY),
+            (delay, _) => delay,
+        }
+    }
+
+    // Metadata for each output file, with the channel mapping of everything in it
+    pub(crate) fn sinks(&self, usrp:&USRP) -> Result<Vec<Sink>, &'static str> {
+        let groups:Vec<Vec<usize>> = match self.layout {
+            Layout::FilePerChannel => (0..self.chans.len()).map(|idx| vec![idx]).collect(),
+            Layout::Interleaved => vec![(0..self.chans.len()).collect()],
+        };
+
+        let mut ans = vec![];
+        for block_idx in groups {
+            let chans:Vec<usize> = block_idx.iter().map(|idx| self.chans[*idx]).collect();
+            let mut global = Global::for_rx(usrp, &chans)?;
+            let mut mappings = vec![];
+            for chan in chans.iter() {
+                mappings.push(ChannelMapping::read(usrp, *chan)?);
+            }
+            global.extra.insert("uhd_rs:channels".to_owned(), serde_json::to_value(&mappings).map_err(|_| "Unable to serialize channel mapping")?);
+
+            // SigMF captures apply to every channel in a file, so per-channel tuning is only in the mapping
+            let mut capture = Capture::for_rx(usrp, chans[0])?;
+            if mappings.iter().any(|m| m.config.freq != capture.frequency) {
+                capture.frequency = None;
+            }
+            if mappings.iter().any(|m| m.config.gain != capture.gain) {
+                capture.gain = None;
+            }
+            ans.push(Sink{ global, capture, chans, block_idx });
+        }
+        Ok(ans)
+    }
+
+    // Most a single file holding `num_chans` channels can grow to, if rotation limits it
+    pub fn file_bytes(&self, rate:f64, num_chans:usize) -> Option<u64> {
+        let by_time = self.rotate_secs.map(|secs| (secs * rate).ceil() as u64 * 4 * num_chans as u64);
+        match (self.rotate_bytes, by_time) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    // The writer thread: rotates every file together as blocks come in and returns early if free space drops below
+    // the limit.  Files still open when it stops, including on an error, are finished so each is trimmed and has its
+    // metadata; the first error is the one returned.
+    pub(crate) fn write_blocks(&self, blocks:Receiver<Block>, sinks:&[Sink])
+        -> Result<(Vec<FileSummary>, Option<StopReason>), &'static str> {
+
+        let mut files:Vec<FileSummary> = vec![];
+        let mut current:Vec<SigMfWriter> = vec![];
+        let result = self.write_into(blocks, sinks, &mut files, &mut current);
+        let finished = finish_files(&mut current, &files);
+        let reason = result?;
+        finished?;
+        Ok((files, reason))
+    }
+
+    fn write_into(&self, blocks:Receiver<Block>, sinks:&[Sink], files:&mut Vec<FileSummary>, current:&mut Vec<SigMfWriter>)
+        -> Result<Option<StopReason>, &'static str> {
+
+        let rate = sinks.first().and_then(|s| s.global.sample_rate).ok_or("Unknown sample rate")?;
+        let file_bytes:Vec<Option<u64>> = sinks.iter().map(|s| self.file_bytes(rate, s.chans.len())).collect();
+        let mut rotations = 0;
+        let mut checked_at:u64 = 0;
+
+        for block in blocks {
+            let full = current.iter().any(|w| {
+                self.rotate_bytes.map(|max| w.bytes_written() >= max).unwrap_or(false) ||
+                self.rotate_secs.map(|max| w.samples_written() as f64 / rate >= max).unwrap_or(false)
+            });
+
+            if current.is_empty() || full {
+                finish_files(current, files)?;
+                let needed:u64 = file_bytes.iter().map(|b| b.unwrap_or(0)).sum();
+                if free_space(&self.dir)? < self.min_free_bytes + needed {
+                    return Ok(Some(StopReason::DiskSpace));
+                }
+
+                let stamp = file_stamp(SystemTime::now());
+                for (sink, bytes) in sinks.iter().zip(file_bytes.iter()) {
+                    let name = match sinks.len() {
+                        1 => format!("{}_{}_{:03}", self.prefix, stamp, rotations),
+                        _ => format!("{}_{}_{:03}_ch{}", self.prefix, stamp, rotations, sink.chans[0]),
+                    };
+                    let mut writer = SigMfWriter::create(self.dir.join(name), sink.global.clone())?;
+                    // Only the first file can be checked against the requested start time
+                    let mut capture = sink.capture.clone();
+                    if rotations > 0 {
+                        capture.time_spec = None;
+                    }
+                    writer.start_capture(capture);
+
+                    files.push(FileSummary {
+                        data_path: writer.data_path().to_path_buf(), chans: sink.chans.clone(),
+                        samples: 0, start: None, end: None, overflows: 0, misalignments: 0
+                    });
+                    current.push(writer);
+                    if let (true, Some(bytes), Some(writer)) = (self.preallocate, bytes, current.last_mut()) {
+                        writer.preallocate(*bytes)?;
+                    }
+                }
+                rotations += 1;
+                checked_at = 0;
+            }
+
+            let written:u64 = current.iter().map(|w| w.bytes_written()).sum();
+            if written >= checked_at + SPACE_CHECK_BYTES {
+                checked_at = written;
+                if free_space(&self.dir)? < self.min_free_bytes {
+                    return Ok(Some(StopReason::DiskSpace));
+                }
+            }
+
+            let open = files.len() - current.len();
+            let len = block.samples.first().map(|s| s.len()).unwrap_or(0);
+            for ((writer, summary), sink) in current.iter_mut().zip(files[open..].iter_mut()).zip(sinks) {
+                if block.overflow {
+                    writer.annotate_overflow();
+                    summary.overflows += 1;
+                }
+                if block.misaligned {
+                    writer.annotate(Annotation::new(writer.samples_written(), None, "misalignment", "UHD reported the channels out of alignment"));
+                    summary.misalignments += 1;
+                }
+                if len == 0 {
+                    continue;
+                }
+
+                match sink.block_idx.as_slice() {
+                    [idx] => writer.write_sc16_at(&block.samples[*idx], block.time_spec)?,
+                    idxs => {
+                        let interleaved:Vec<(i16, i16)> = (0..len).flat_map(|n| idxs.iter().map(move |idx| (n, *idx)))
+                            .map(|(n, idx)| block.samples[idx][n]).collect();
+                        writer.write_sc16_at(&interleaved, block.time_spec)?
+                    }
+                }
+                summary.samples += len as u64;
+                summary.start = summary.start.or(Some(block.time_spec));
+                summary.end = Some(time_add(block.time_spec, len as f64 / rate));
+            }
+        }
+
+        Ok(None)
+    }
+
+}
+
+// Finishes every open writer, which are the last `current.len()` entries in `files`, even if one of them fails
+fn finish_files(current:&mut Vec<SigMfWriter>, files:&[FileSummary]) -> Result<(), &'static str> {
+    let open = files.len() - current.len();
+    let mut ans = Ok(());
+    for (writer, summary) in current.drain(..).zip(files[open..].iter()) {
+        ans = ans.and(finish_file(writer, summary));
+    }
+    ans
+}
+
+// Adds the file's time range and error counts to its SigMF metadata, which serves as the sidecar
+fn finish_file(mut writer:SigMfWriter, summary:&FileSummary) -> Result<(), &'static str> {
+    let extra = &mut writer.meta_mut().global.extra;
+    extra.insert("uhd_rs:time_start".to_owned(), json!(summary.start));
+    extra.insert("uhd_rs:time_end".to_owned(), json!(summary.end));
+    extra.insert("uhd_rs:overflow_count".to_owned(), json!(summary.overflows));
+    extra.insert("uhd_rs:misalignment_count".to_owned(), json!(summary.misalignments));
+    writer.finish().map(|_| ())
+}
+
+// Bytes available to unprivileged users on the filesystem holding `path`
+#[cfg(unix)]
+pub fn free_space<P: AsRef<Path>>(path:P) -> Result<u64, &'static str> {
+    let path_c = std::ffi::CString::new(path.as_ref().to_str().ok_or("Path isn't valid UTF-8")?).map_err(|_| "Path can't contain a nul byte")?;
+    let mut stat:libc::statvfs = unsafe { std::mem::zeroed() };
+    match unsafe { libc::statvfs(path_c.as_ptr(), &mut stat) } {
+        0 => Ok(stat.f_bavail as u64 * stat.f_frsize as u64),
+        _ => Err("Unable to get free disk space"),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn free_space<P: AsRef<Path>>(_path:P) -> Result<u64, &'static str> {
+    Err("Checking free disk space is only supported on Unix")
+}
+
+// Compact UTC time for file names, like 20210304T050607Z
+pub fn file_stamp(time:SystemTime) -> String {
+    let secs = time.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let full = sigmf::iso8601((secs, 0.0));
+    let whole = full.split('.').next().unwrap_or(&full);
+    format!("{}Z", whole.replace(['-', ':'], ""))
+}
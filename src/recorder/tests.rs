@@ -0,0 +1,164 @@
+use std::sync::mpsc::sync_channel;
+use std::time::{Duration, SystemTime};
+
+use crate::io::sigmf::{Capture, Global, SigMfReader};
+use crate::recorder::{self, Block, Recorder, Sink, StopReason};
+use crate::test_util::TempDir;
+
+fn blocks(n:usize, len:usize, chans:usize, rate:f64) -> Vec<Block> {
+    (0..n).map(|idx| Block {
+        samples: (0..chans).map(|chan| vec![(idx as i16, chan as i16); len]).collect(),
+        time_spec: (5, (idx * len) as f64 / rate),
+        overflow: idx == 2,
+        misaligned: idx == 3,
+    }).collect()
+}
+
+fn sink(chans:&[usize], block_idx:&[usize], rate:f64) -> Sink {
+    Sink {
+        global: Global::ci16(rate, chans.len()),
+        capture: Capture::new(1e9, 0.0),
+        chans: chans.to_vec(),
+        block_idx: block_idx.to_vec(),
+    }
+}
+
+#[test]
+fn rotation_and_sidecars() {
+    let dir = TempDir::new("recorder");
+
+    let recorder = Recorder::new(dir.path(), "test").rotate_bytes(800).min_free_bytes(0).preallocate(true);
+    let (sender, receiver) = sync_channel(16);
+    for block in blocks(5, 100, 1, 1e4) {
+        sender.send(block).unwrap();
+    }
+    drop(sender);
+
+    let (files, reason) = recorder.write_blocks(receiver, &[sink(&[0], &[0], 1e4)]).unwrap();
+    assert_eq!(None, reason);
+    assert_eq!(vec![200, 200, 100], files.iter().map(|f| f.samples).collect::<Vec<u64>>());
+    assert_eq!(Some((5, 0.02)), files[1].start);
+    assert_eq!(Some((5, 0.05)), files[2].end);
+    assert_eq!(1, files[1].overflows);
+    assert_eq!(1, files[1].misalignments);
+
+    let reader = SigMfReader::open(&files[1].data_path).unwrap();
+    assert_eq!(200, reader.num_samples());
+    assert_eq!(800, std::fs::metadata(&files[1].data_path).unwrap().len());
+    assert_eq!(Some(&serde_json::json!(1)), reader.meta.global.extra.get("uhd_rs:overflow_count"));
+    assert_eq!(Some("overflow"), reader.meta.annotations[0].label.as_deref());
+    assert_eq!(Some("misalignment"), reader.meta.annotations[1].label.as_deref());
+}
+
+#[test]
+fn zero_length_overflow_block() {
+    let dir = TempDir::new("recorder_overflow");
+
+    // UHD reports an overflow as a packet with no samples in it
+    let mut input = blocks(2, 100, 1, 1e3);
+    input.iter_mut().for_each(|b| { b.overflow = false; b.misaligned = false; });
+    input.insert(1, Block{ samples: vec![vec![]], time_spec: (5, 0.1), overflow: true, misaligned: false });
+
+    let recorder = Recorder::new(dir.path(), "overflow").min_free_bytes(0);
+    let (sender, receiver) = sync_channel(16);
+    for block in input {
+        sender.send(block).unwrap();
+    }
+    drop(sender);
+
+    let (files, _) = recorder.write_blocks(receiver, &[sink(&[0], &[0], 1e3)]).unwrap();
+    assert_eq!(1, files.len());
+    assert_eq!(200, files[0].samples);
+    assert_eq!(1, files[0].overflows);
+
+    let reader = SigMfReader::open(&files[0].data_path).unwrap();
+    assert_eq!(Some("overflow"), reader.meta.annotations[0].label.as_deref());
+    assert_eq!(100, reader.meta.annotations[0].sample_start);
+}
+
+#[test]
+fn multi_channel_layouts() {
+    let dir = TempDir::new("recorder_multi");
+
+    let recorder = Recorder::new(dir.path(), "multi").min_free_bytes(0);
+    let (sender, receiver) = sync_channel(16);
+    for block in blocks(2, 10, 2, 1e3) {
+        sender.send(block).unwrap();
+    }
+    drop(sender);
+    let (files, _) = recorder.write_blocks(receiver, &[sink(&[2], &[0], 1e3), sink(&[3], &[1], 1e3)]).unwrap();
+    assert_eq!(2, files.len());
+    assert!(files[1].data_path.to_str().unwrap().ends_with("_000_ch3.sigmf-data"));
+    assert_eq!(&[(1, 1); 10], &SigMfReader::open(&files[1].data_path).unwrap().ci16().unwrap()[10..]);
+
+    let (sender, receiver) = sync_channel(16);
+    for block in blocks(2, 10, 2, 1e3) {
+        sender.send(block).unwrap();
+    }
+    drop(sender);
+    let (files, _) = recorder.write_blocks(receiver, &[sink(&[2, 3], &[0, 1], 1e3)]).unwrap();
+    let reader = SigMfReader::open(&files[0].data_path).unwrap();
+    assert_eq!(20, reader.num_samples());
+    assert_eq!(&[(0, 0), (0, 1), (0, 0)], &reader.ci16().unwrap()[..3]);
+}
+
+#[test]
+fn late_start_is_flagged() {
+    let dir = TempDir::new("recorder_late");
+
+    let recorder = Recorder::new(dir.path(), "late").min_free_bytes(0);
+    let mut late = sink(&[0], &[0], 1e3);
+    late.capture.time_spec = Some((4, 0.99));
+    let (sender, receiver) = sync_channel(16);
+    sender.send(blocks(1, 10, 1, 1e3).remove(0)).unwrap();
+    drop(sender);
+
+    let (files, _) = recorder.write_blocks(receiver, &[late]).unwrap();
+    let reader = SigMfReader::open(&files[0].data_path).unwrap();
+    assert_eq!(Some("gap"), reader.meta.annotations[0].label.as_deref());
+    assert_eq!(Some((5, 0.0)), reader.time_of_sample(0));
+}
+
+#[test]
+fn files_are_finished_on_error() {
+    let dir = TempDir::new("recorder_error");
+
+    // Writing sc16 blocks into a cf32 recording fails after the file has been opened
+    let recorder = Recorder::new(dir.path(), "error").min_free_bytes(0).preallocate(true);
+    let mut float = sink(&[0], &[0], 1e3);
+    float.global = Global::cf32(1e3, 1);
+    let (sender, receiver) = sync_channel(1);
+    sender.send(blocks(1, 10, 1, 1e3).remove(0)).unwrap();
+    drop(sender);
+
+    assert!(recorder.write_blocks(receiver, &[float]).is_err());
+    let reader = SigMfReader::open_dir(dir.path()).unwrap().remove(0);
+    assert_eq!(0, reader.num_samples());
+    assert_eq!(0, std::fs::metadata(reader.data_path()).unwrap().len());
+}
+
+#[test]
+fn stops_before_disk_is_full() {
+    let dir = TempDir::new("recorder_full");
+    let recorder = Recorder::new(dir.path(), "full").min_free_bytes(u64::MAX / 2);
+    let (sender, receiver) = sync_channel(1);
+    sender.send(blocks(1, 10, 1, 1e3).remove(0)).unwrap();
+
+    let (files, reason) = recorder.write_blocks(receiver, &[sink(&[0], &[0], 1e3)]).unwrap();
+    assert!(files.is_empty());
+    assert_eq!(Some(StopReason::DiskSpace), reason);
+}
+
+#[test]
+fn multi_channel_starts_timed() {
+    assert_eq!(None, Recorder::new(".", "x").effective_start_delay());
+    assert_eq!(Some(2.0), Recorder::new(".", "x").start_delay(2.0).effective_start_delay());
+    assert!(Recorder::new(".", "x").chans(&[0, 1]).effective_start_delay().unwrap() > 0.0);
+    assert_eq!(Some(2.0), Recorder::new(".", "x").chans(&[0, 1]).start_delay(2.0).effective_start_delay());
+}
+
+#[test]
+fn file_names() {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1614834367);
+    assert_eq!("20210304T050607Z", recorder::file_stamp(time));
+}
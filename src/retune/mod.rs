@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+use crate::lo_sharing::Direction;
+use crate::timing::time_sync::{time_add, time_diff};
+use crate::types::{TimeSpec, TuneResult};
+use crate::types::sensors::Sensor;
+use crate::usrp::{ALL_MBOARDS, USRP};
+
+#[cfg(test)]
+mod tests;
+
+// Frequency UHD actually ended up at, given the sign it applies to the DSP shift in each direction
+pub fn achieved_freq(direction:Direction, result:&TuneResult) -> f64 {
+    match direction {
+        Direction::Rx => result.actual_rf_freq - result.actual_dsp_freq,
+        Direction::Tx => result.actual_rf_freq + result.actual_dsp_freq,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelTuneCheck {
+    pub chan:usize,
+    pub requested_hz:f64,
+    pub result:TuneResult,
+    pub clipped:bool,               // The request was outside the range of the frontend
+    pub residual_hz:f64,            // Achieved minus requested; mix by the negative of this to correct in software
+    pub lo_locked:Option<bool>,     // None if the channel has no LO lock sensor
+}
+
+impl ChannelTuneCheck {
+
+    pub fn new(direction:Direction, chan:usize, requested_hz:f64, result:TuneResult, tolerance_hz:f64) -> Self {
+        let clipped = (result.clipped_rf_freq - requested_hz).abs() > tolerance_hz;
+        let residual_hz = achieved_freq(direction, &result) - requested_hz;
+        Self{ chan, requested_hz, result, clipped, residual_hz, lo_locked: None }
+    }
+
+    pub fn within_tolerance(&self, tolerance_hz:f64) -> bool {
+        !self.clipped && self.residual_hz.abs() <= tolerance_hz
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetuneReport {
+    pub command_time:TimeSpec,
+    pub tolerance_hz:f64,
+    pub lock_time:Duration,         // From the command time passing to every LO reporting lock
+    pub channels:Vec<ChannelTuneCheck>,
+}
+
+impl RetuneReport {
+
+    pub fn all_within_tolerance(&self) -> bool {
+        self.channels.iter().all(|c| c.within_tolerance(self.tolerance_hz))
+    }
+
+    pub fn all_locked(&self) -> bool {
+        self.channels.iter().all(|c| c.lo_locked != Some(false))
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.all_within_tolerance() && self.all_locked()
+    }
+
+    // Residual DSP offset per channel, in the same order the channels were given
+    pub fn residuals(&self) -> Vec<(usize, f64)> {
+        self.channels.iter().map(|c| (c.chan, c.residual_hz)).collect()
+    }
+
+}
+
+// Retunes a group of channels with a single timed command so every LO and DDC changes on the same clock edge,
+// which keeps the relative phase between channels repeatable from one retune to the next
+#[derive(Clone, Debug, PartialEq)]
+pub struct Retune {
+    pub direction:Direction,
+    pub channels:Vec<usize>,
+    pub lead_time:f64,
+    pub tolerance_hz:f64,
+    pub lock_timeout:Duration,
+    pub poll_interval:Duration,
+}
+
+impl Retune {
+
+    pub fn new(direction:Direction, channels:&[usize]) -> Self {
+        Self {
+            direction, channels: channels.to_vec(),
+            lead_time: 0.1,
+            tolerance_hz: 1.0,
+            lock_timeout: Duration::from_secs(1),
+            poll_interval: Duration::from_millis(1),
+        }
+    }
+
+    pub fn lead_time(mut self, secs:f64) -> Self { self.lead_time = secs; self }
+    pub fn tolerance_hz(mut self, tolerance_hz:f64) -> Self { self.tolerance_hz = tolerance_hz; self }
+    pub fn lock_timeout(mut self, timeout:Duration) -> Self { self.lock_timeout = timeout; self }
+    pub fn poll_interval(mut self, interval:Duration) -> Self { self.poll_interval = interval; self }
+
+    pub fn run(&self, usrp:&mut USRP, freq_hz:f64) -> Result<RetuneReport, &'static str> {
+        if self.channels.is_empty() {
+            return Err("Retune needs at least one channel");
+        }
+
+        let command_time = time_add(usrp.get_time_now(0)?, self.lead_time);
+        let (full_secs, frac_secs) = command_time;
+
+        usrp.set_command_time(full_secs, frac_secs, ALL_MBOARDS)?;
+        let mut results = vec![];
+        for chan in self.channels.iter() {
+            let result = match self.direction {
+                Direction::Rx => usrp.set_rx_freq_auto(freq_hz, *chan),
+                Direction::Tx => usrp.set_tx_freq_auto(freq_hz, *chan),
+            };
+            match result {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    usrp.clear_command_time(ALL_MBOARDS)?;
+                    return Err(e);
+                }
+            }
+        }
+        usrp.clear_command_time(ALL_MBOARDS)?;
+
+        let mut channels:Vec<ChannelTuneCheck> = self.channels.iter().zip(results)
+            .map(|(chan, result)| ChannelTuneCheck::new(self.direction, *chan, freq_hz, result, self.tolerance_hz))
+            .collect();
+
+        // LO lock sensors don't mean anything until the timed tune has actually happened
+        let t0 = Instant::now();
+        while time_diff(usrp.get_time_now(0)?, command_time) < 0.0 {
+            if t0.elapsed().as_secs_f64() > self.lead_time + self.lock_timeout.as_secs_f64() {
+                return Err("Device time never reached the retune command time");
+            }
+            std::thread::sleep(self.poll_interval);
+        }
+
+        let t0 = Instant::now();
+        for check in channels.iter_mut() {
+            check.lo_locked = self.wait_for_lock(usrp, check.chan, t0)?;
+        }
+
+        Ok(RetuneReport{ command_time, tolerance_hz: self.tolerance_hz, lock_time: t0.elapsed(), channels })
+    }
+
+    // Returns the final reading, which is false only if the lock timeout ran out first
+    fn wait_for_lock(&self, usrp:&USRP, chan:usize, t0:Instant) -> Result<Option<bool>, &'static str> {
+        let available = match self.direction {
+            Direction::Rx => usrp.get_rx_sensor_names(chan)?,
+            Direction::Tx => usrp.get_tx_sensor_names(chan)?,
+        };
+        if !available.iter().any(|s| s == Sensor::LoLocked.name()) {
+            return Ok(None);
+        }
+
+        loop {
+            let locked = match self.direction {
+                Direction::Rx => usrp.get_rx_sensor(Sensor::LoLocked, chan)?,
+                Direction::Tx => usrp.get_tx_sensor(Sensor::LoLocked, chan)?,
+            }.to_bool()?;
+
+            if locked || t0.elapsed() > self.lock_timeout {
+                return Ok(Some(locked));
+            }
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+
+}
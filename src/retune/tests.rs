@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use crate::lo_sharing::Direction;
+use crate::retune::{achieved_freq, ChannelTuneCheck, RetuneReport};
+use crate::types::TuneResult;
+
+fn result(clipped_rf_freq:f64, actual_rf_freq:f64, actual_dsp_freq:f64) -> TuneResult {
+    TuneResult{
+        clipped_rf_freq, target_rf_freq: clipped_rf_freq, actual_rf_freq,
+        target_dsp_freq: actual_dsp_freq, actual_dsp_freq
+    }
+}
+
+#[test]
+fn dsp_sign_per_direction() {
+    // The LO lands 250 kHz high and the DDC/DUC makes up the difference with opposite signs
+    let rx = result(915.0e6, 915.25e6, 250.0e3);
+    let tx = result(915.0e6, 915.25e6, -250.0e3);
+    assert_eq!(915.0e6, achieved_freq(Direction::Rx, &rx));
+    assert_eq!(915.0e6, achieved_freq(Direction::Tx, &tx));
+}
+
+#[test]
+fn residual_and_tolerance() {
+    let check = ChannelTuneCheck::new(Direction::Rx, 0, 915.0e6, result(915.0e6, 915.25e6, 249.9995e3), 1.0);
+    assert!(!check.clipped);
+    assert!((check.residual_hz - 0.5).abs() < 1.0e-6);
+    assert!(check.within_tolerance(1.0));
+    assert!(!check.within_tolerance(0.1));
+
+    // Asking for more than the frontend can do shows up as a clipped request even if the DSP tuned exactly
+    let check = ChannelTuneCheck::new(Direction::Rx, 1, 7.0e9, result(6.0e9, 6.0e9, 0.0), 1.0);
+    assert!(check.clipped);
+    assert!(!check.within_tolerance(1.0e12));
+}
+
+#[test]
+fn report_checks() {
+    let mut a = ChannelTuneCheck::new(Direction::Rx, 0, 915.0e6, result(915.0e6, 915.0e6, 0.0), 1.0);
+    let mut b = ChannelTuneCheck::new(Direction::Rx, 1, 915.0e6, result(915.0e6, 915.0e6, -0.25), 1.0);
+    a.lo_locked = Some(true);
+    b.lo_locked = None;
+
+    let mut report = RetuneReport{ command_time: (1, 0.5), tolerance_hz: 1.0, lock_time: Duration::from_millis(2), channels: vec![a, b] };
+    assert!(report.is_ok());
+    assert_eq!(vec![(0, 0.0), (1, 0.25)], report.residuals());
+
+    report.channels[1].lo_locked = Some(false);
+    assert!(report.all_within_tolerance());
+    assert!(!report.is_ok());
+}
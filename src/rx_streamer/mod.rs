@@ -47,6 +47,7 @@ impl RxStreamer {
 	}
 
 	pub fn get_handle(&self) -> usize { self.handle }
+	pub fn overflow_count(&self) -> usize { self.overflow_count }
 
 	pub fn recv_one_multi_chan(&mut self, buffs: &mut [&mut [(i16, i16)]]) -> Result<(usize, (i64, f64)), &'static str> {
 		if buffs.len() != self.num_chans {
@@ -137,6 +138,10 @@ impl RxStreamer {
 		}
 	}
 
+	pub fn rx_metadata_error_code(&self) -> Result<RxMetadataErrorCode, &'static str> {
+		self.rx_metadata.error_code()
+	}
+
 	pub fn rx_metadata_ok(&mut self) -> Result<(), &'static str> {
 		match self.rx_metadata.error_code()? {
 			RxMetadataErrorCode::None => Ok(()),
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::rx_streamer::RxStreamer;
+use crate::timing::time_sync::time_diff;
+use crate::types::TimeSpec;
+use crate::usrp::{StreamCmd, StreamMode, USRP};
+
+pub use crate::usrp::ALL_MBOARDS;
+
+#[cfg(test)]
+mod tests;
+
+// Conservative default; the actual depth depends on the device and FPGA image
+pub const DEFAULT_QUEUE_DEPTH:usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    TuneRx{ chan:usize, freq_hz:f64 },
+    TuneTx{ chan:usize, freq_hz:f64 },
+    SetRxGain{ chan:usize, gain_db:f64 },
+    SetTxGain{ chan:usize, gain_db:f64 },
+    GpioWrite{ bank:String, attr:String, value:u32, mask:u32 },
+    StartStream,    // Issued to the RX streamer passed to `CommandSchedule::execute`
+    StopStream,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledCommand {
+    pub time:TimeSpec,
+    pub mboard:usize,
+    pub action:Action,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandOutcome {
+    pub command:ScheduledCommand,
+    pub issued_at:TimeSpec,     // Device time just before the command was issued
+    pub lead_secs:f64,          // How far ahead of its execution time the command was issued; negative if late
+}
+
+impl CommandOutcome {
+
+    pub fn is_late(&self) -> bool {
+        self.lead_secs < 0.0
+    }
+
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScheduleReport {
+    pub outcomes:Vec<CommandOutcome>,
+}
+
+impl ScheduleReport {
+
+    pub fn late(&self) -> Vec<&CommandOutcome> {
+        self.outcomes.iter().filter(|o| o.is_late()).collect()
+    }
+
+}
+
+// Bookkeeping for timed commands that have been issued but whose execution time hasn't passed yet
+#[derive(Clone, Debug)]
+pub struct CommandQueue {
+    depth:usize,
+    pending:VecDeque<TimeSpec>,
+}
+
+impl CommandQueue {
+
+    pub fn new(depth:usize) -> Self {
+        Self{ depth: depth.max(1), pending: VecDeque::new() }
+    }
+
+    // Drops every pending command that should have executed by device time `now`
+    pub fn retire(&mut self, now:TimeSpec) {
+        while self.pending.front().map(|t| time_diff(*t, now) <= 0.0).unwrap_or(false) {
+            self.pending.pop_front();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.pending.len() >= self.depth
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn push(&mut self, time:TimeSpec) {
+        self.pending.push_back(time);
+    }
+
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandSchedule {
+    commands:Vec<ScheduledCommand>,
+    pub queue_depth:usize,
+    pub poll_interval:Duration,
+}
+
+impl CommandSchedule {
+
+    pub fn new() -> Self {
+        Self{ commands: vec![], queue_depth: DEFAULT_QUEUE_DEPTH, poll_interval: Duration::from_millis(1) }
+    }
+
+    pub fn queue_depth(mut self, depth:usize) -> Self {
+        self.queue_depth = depth;
+        self
+    }
+
+    pub fn at(self, time:TimeSpec, action:Action) -> Self {
+        self.at_mboard(time, ALL_MBOARDS, action)
+    }
+
+    pub fn at_mboard(mut self, time:TimeSpec, mboard:usize, action:Action) -> Self {
+        self.commands.push(ScheduledCommand{ time, mboard, action });
+        self
+    }
+
+    // Commands in the order they'll be issued; ties keep the order they were added in
+    pub fn sorted(&self) -> Vec<ScheduledCommand> {
+        let mut ans = self.commands.clone();
+        ans.sort_by(|a, b| time_diff(a.time, b.time).partial_cmp(&0.0).unwrap_or(std::cmp::Ordering::Equal));
+        ans
+    }
+
+    pub fn execute(&self, usrp:&mut USRP, mut rx_streamer:Option<&mut RxStreamer>) -> Result<ScheduleReport, &'static str> {
+        let mut queue = CommandQueue::new(self.queue_depth);
+        let mut report = ScheduleReport::default();
+
+        for command in self.sorted() {
+            let time_mboard = if command.mboard == ALL_MBOARDS { 0 } else { command.mboard };
+
+            let mut now = usrp.get_time_now(time_mboard)?;
+            queue.retire(now);
+            while queue.is_full() {
+                std::thread::sleep(self.poll_interval);
+                now = usrp.get_time_now(time_mboard)?;
+                queue.retire(now);
+            }
+
+            let (full_secs, frac_secs) = command.time;
+            match &command.action {
+                Action::StartStream | Action::StopStream => {
+                    let streamer = rx_streamer.as_deref_mut().ok_or("Stream commands need an RX streamer")?;
+                    let stream_mode = match command.action {
+                        Action::StartStream => StreamMode::StartContinuous,
+                        _ => StreamMode::StopContinuous,
+                    };
+                    streamer.stream(&StreamCmd{
+                        stream_mode, num_samps: 0, stream_now: false,
+                        time_spec_full_secs: full_secs, time_spec_frac_secs: frac_secs
+                    })?;
+                },
+                action => {
+                    usrp.set_command_time(full_secs, frac_secs, command.mboard)?;
+                    let result = issue(usrp, action, command.mboard);
+                    usrp.clear_command_time(command.mboard)?;
+                    result?;
+                },
+            }
+
+            queue.push(command.time);
+            let lead_secs = time_diff(command.time, now);
+            report.outcomes.push(CommandOutcome{ command, issued_at: now, lead_secs });
+        }
+
+        Ok(report)
+    }
+
+}
+
+impl Default for CommandSchedule {
+    fn default() -> Self { Self::new() }
+}
+
+fn issue(usrp:&mut USRP, action:&Action, mboard:usize) -> Result<(), &'static str> {
+    match action {
+        Action::TuneRx{ chan, freq_hz } => usrp.set_rx_freq_auto(*freq_hz, *chan).map(|_| ()),
+        Action::TuneTx{ chan, freq_hz } => usrp.set_tx_freq_auto(*freq_hz, *chan).map(|_| ()),
+        Action::SetRxGain{ chan, gain_db } => usrp.set_rx_gain(*gain_db, *chan, ""),
+        Action::SetTxGain{ chan, gain_db } => usrp.set_tx_gain(*gain_db, *chan, ""),
+        Action::GpioWrite{ bank, attr, value, mask } => {
+            let mboards:Vec<usize> = if mboard == ALL_MBOARDS { (0..usrp.num_mboards()?).collect() } else { vec![mboard] };
+            for mboard in mboards {
+                usrp.set_gpio_attr(bank, attr, *value, *mask, mboard)?;
+            }
+            Ok(())
+        },
+        Action::StartStream | Action::StopStream => Err("Stream commands aren't issued through the command time"),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::schedule::{Action, CommandOutcome, CommandQueue, CommandSchedule, ScheduledCommand, ALL_MBOARDS};
+
+#[test]
+fn commands_sorted_by_time() {
+    let schedule = CommandSchedule::new()
+        .at((2, 0.5), Action::TuneRx{ chan: 0, freq_hz: 915.0e6 })
+        .at((1, 0.75), Action::SetRxGain{ chan: 0, gain_db: 30.0 })
+        .at_mboard((2, 0.5), 1, Action::StartStream)
+        .at((1, 0.25), Action::StopStream);
+
+    let times:Vec<(i64, f64)> = schedule.sorted().iter().map(|c| c.time).collect();
+    assert_eq!(times, vec![(1, 0.25), (1, 0.75), (2, 0.5), (2, 0.5)]);
+
+    // Ties keep the order they were added in
+    let sorted = schedule.sorted();
+    assert_eq!(ALL_MBOARDS, sorted[2].mboard);
+    assert_eq!(1, sorted[3].mboard);
+}
+
+#[test]
+fn queue_depth_bookkeeping() {
+    let mut queue = CommandQueue::new(2);
+    queue.push((10, 0.1));
+    queue.push((10, 0.2));
+    assert!(queue.is_full());
+
+    queue.retire((10, 0.05));
+    assert_eq!(2, queue.len());
+
+    queue.retire((10, 0.15));
+    assert_eq!(1, queue.len());
+    assert!(!queue.is_full());
+
+    queue.retire((11, 0.0));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn lateness() {
+    let command = ScheduledCommand{ time: (5, 0.0), mboard: 0, action: Action::StopStream };
+    let on_time = CommandOutcome{ command: command.clone(), issued_at: (4, 0.9), lead_secs: 0.1 };
+    let late = CommandOutcome{ command, issued_at: (5, 0.1), lead_secs: -0.1 };
+    assert!(!on_time.is_late());
+    assert!(late.is_late());
+}
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::types::sensors::{SensorReading, SensorValue};
+use crate::usrp::USRP;
+
+#[cfg(test)]
+mod tests;
+
+// How often the polling thread wakes up to check whether it's been asked to stop
+const STOP_CHECK_PERIOD:Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SensorSource {
+    Mboard(usize),
+    Rx(usize),
+    Tx(usize),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SensorId {
+    pub source:SensorSource,
+    pub name:String,
+}
+
+impl SensorId {
+
+    pub fn new<S: AsRef<str>>(source:SensorSource, name:S) -> Self {
+        Self{ source, name: name.as_ref().to_owned() }
+    }
+
+    fn read(&self, usrp:&USRP) -> Result<SensorValue, &'static str> {
+        match self.source {
+            SensorSource::Mboard(mboard) => usrp.get_mboard_sensor(&self.name, mboard),
+            SensorSource::Rx(chan) => usrp.get_rx_sensor(&self.name, chan),
+            SensorSource::Tx(chan) => usrp.get_tx_sensor(&self.name, chan),
+        }
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MonitoredValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl MonitoredValue {
+
+    pub fn from_sensor_value(value:&SensorValue) -> Result<Self, &'static str> {
+        Ok(Self::from(value.read()?))
+    }
+
+}
+
+impl From<SensorReading> for MonitoredValue {
+
+    fn from(reading:SensorReading) -> Self {
+        match reading {
+            SensorReading::Bool{ value, .. }   => MonitoredValue::Bool(value),
+            SensorReading::Int{ value, .. }    => MonitoredValue::Number(value as f64),
+            SensorReading::Real{ value, .. }   => MonitoredValue::Number(value),
+            SensorReading::String{ value, .. } => MonitoredValue::Text(value),
+        }
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub time:SystemTime,
+    pub value:MonitoredValue,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Crossing { Rising, Falling }
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SensorEvent {
+    // The first reading of a sensor, along with every threshold it's already at or above
+    Initial{ id:SensorId, value:MonitoredValue, above:Vec<f64> },
+    Changed{ id:SensorId, from:bool, to:bool },
+    ThresholdCrossed{ id:SensorId, threshold:f64, value:f64, crossing:Crossing },
+    ReadError{ id:SensorId, error:&'static str },
+}
+
+type Callback = Box<dyn FnMut(&SensorEvent) + Send>;
+
+// Keeps the bounded history for every watched sensor and turns new samples into events.  This is the part of the
+// monitor that doesn't need hardware, so it's kept separate from the polling thread.
+pub struct Tracker {
+    history_len:usize,
+    thresholds:HashMap<SensorId, Vec<f64>>,
+    history:HashMap<SensorId, VecDeque<Sample>>,
+}
+
+impl Tracker {
+
+    pub fn new(history_len:usize) -> Self {
+        Self{ history_len: history_len.max(1), thresholds: HashMap::new(), history: HashMap::new() }
+    }
+
+    pub fn add_threshold(&mut self, id:SensorId, level:f64) {
+        self.thresholds.entry(id).or_default().push(level);
+    }
+
+    pub fn process(&mut self, id:&SensorId, sample:Sample) -> Vec<SensorEvent> {
+        let mut events = vec![];
+        let history = self.history.entry(id.clone()).or_default();
+
+        match (history.back().map(|s| &s.value), &sample.value) {
+            (None, value) => {
+                let above = match value {
+                    MonitoredValue::Number(now) => self.thresholds.get(id).into_iter().flatten().filter(|l| *now >= **l).copied().collect(),
+                    _ => vec![],
+                };
+                events.push(SensorEvent::Initial{ id: id.clone(), value: value.clone(), above });
+            },
+            (Some(MonitoredValue::Bool(prev)), MonitoredValue::Bool(now)) if prev != now => {
+                events.push(SensorEvent::Changed{ id: id.clone(), from: *prev, to: *now });
+            },
+            (Some(MonitoredValue::Number(prev)), MonitoredValue::Number(now)) => {
+                for level in self.thresholds.get(id).into_iter().flatten() {
+                    let crossing = if *prev < *level && *now >= *level {
+                        Some(Crossing::Rising)
+                    } else if *prev >= *level && *now < *level {
+                        Some(Crossing::Falling)
+                    } else {
+                        None
+                    };
+                    if let Some(crossing) = crossing {
+                        events.push(SensorEvent::ThresholdCrossed{ id: id.clone(), threshold: *level, value: *now, crossing });
+                    }
+                }
+            },
+            _ => (),
+        }
+
+        if history.len() >= self.history_len {
+            history.pop_front();
+        }
+        history.push_back(sample);
+
+        events
+    }
+
+    pub fn history(&self, id:&SensorId) -> Vec<Sample> {
+        self.history.get(id).map(|h| h.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    pub fn latest(&self, id:&SensorId) -> Option<Sample> {
+        self.history.get(id).and_then(|h| h.back().cloned())
+    }
+
+}
+
+pub struct SensorMonitor {
+    interval:Duration,
+    watches:Vec<SensorId>,
+    tracker:Tracker,
+    callbacks:Vec<Callback>,
+}
+
+impl SensorMonitor {
+
+    pub fn new(interval:Duration, history_len:usize) -> Self {
+        Self{ interval, watches: vec![], tracker: Tracker::new(history_len), callbacks: vec![] }
+    }
+
+    pub fn watch<S: AsRef<str>>(mut self, source:SensorSource, name:S) -> Self {
+        let id = SensorId::new(source, name);
+        if !self.watches.contains(&id) {
+            self.watches.push(id);
+        }
+        self
+    }
+
+    // Watches the sensor as well, so there's no need to call `watch` separately
+    pub fn threshold<S: AsRef<str>>(mut self, source:SensorSource, name:S, level:f64) -> Self {
+        self = self.watch(source, name.as_ref());
+        self.tracker.add_threshold(SensorId::new(source, name), level);
+        self
+    }
+
+    pub fn on_event<F: FnMut(&SensorEvent) + Send + 'static>(mut self, callback:F) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    // The USRP is shared with the caller, who needs to hold the lock for as short a time as possible while the
+    // monitor is running so that polling doesn't fall behind
+    pub fn start(self, usrp:Arc<Mutex<USRP>>) -> MonitorHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let tracker = Arc::new(Mutex::new(self.tracker));
+
+        let thread = {
+            let stop = stop.clone();
+            let tracker = tracker.clone();
+            let watches = self.watches;
+            let interval = self.interval;
+            let mut callbacks = self.callbacks;
+
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let t0 = Instant::now();
+
+                    for id in watches.iter() {
+                        let reading = match usrp.lock() {
+                            Ok(usrp) => id.read(&usrp).and_then(|v| MonitoredValue::from_sensor_value(&v)),
+                            Err(_) => Err("USRP mutex was poisoned"),
+                        };
+
+                        // Without the tracker there's nowhere to keep history, so the monitor reports why and stops
+                        let (events, poisoned) = match reading {
+                            Ok(value) => {
+                                let sample = Sample{ time: SystemTime::now(), value };
+                                match tracker.lock() {
+                                    Ok(mut tracker) => (tracker.process(id, sample), false),
+                                    Err(_) => (vec![SensorEvent::ReadError{ id: id.clone(), error: "Sensor history mutex was poisoned" }], true),
+                                }
+                            },
+                            Err(error) => (vec![SensorEvent::ReadError{ id: id.clone(), error }], false),
+                        };
+
+                        for event in events.iter() {
+                            for cb in callbacks.iter_mut() {
+                                cb(event);
+                            }
+                        }
+                        if poisoned {
+                            return;
+                        }
+                    }
+
+                    while t0.elapsed() < interval && !stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(STOP_CHECK_PERIOD.min(interval.saturating_sub(t0.elapsed())));
+                    }
+                }
+            })
+        };
+
+        MonitorHandle{ stop, tracker, thread: Some(thread) }
+    }
+
+}
+
+pub struct MonitorHandle {
+    stop:Arc<AtomicBool>,
+    tracker:Arc<Mutex<Tracker>>,
+    thread:Option<JoinHandle<()>>,
+}
+
+impl MonitorHandle {
+
+    pub fn history(&self, id:&SensorId) -> Result<Vec<Sample>, &'static str> {
+        Ok(self.tracker.lock().map_err(|_| "Sensor history mutex was poisoned")?.history(id))
+    }
+
+    pub fn latest(&self, id:&SensorId) -> Result<Option<Sample>, &'static str> {
+        Ok(self.tracker.lock().map_err(|_| "Sensor history mutex was poisoned")?.latest(id))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().map(|t| !t.is_finished()).unwrap_or(false)
+    }
+
+    pub fn stop(&mut self) -> Result<(), &'static str> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread.join().map_err(|_| "Sensor monitor thread panicked"),
+            None => Ok(()),
+        }
+    }
+
+}
+
+impl std::ops::Drop for MonitorHandle {
+
+    fn drop(&mut self) {
+        if self.stop().is_err() {
+            eprintln!("WARN: Sensor monitor thread panicked before it was stopped");
+        }
+    }
+
+}
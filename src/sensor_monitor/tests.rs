@@ -0,0 +1,69 @@
+use std::time::SystemTime;
+
+use crate::sensor_monitor::{Crossing, MonitoredValue, Sample, SensorEvent, SensorId, SensorSource, Tracker};
+
+fn sample(value:MonitoredValue) -> Sample {
+    Sample{ time: SystemTime::now(), value }
+}
+
+#[test]
+fn bool_change_fires_once() {
+    let id = SensorId::new(SensorSource::Mboard(0), "ref_locked");
+    let mut tracker = Tracker::new(8);
+
+    tracker.process(&id, sample(MonitoredValue::Bool(true)));
+    assert!(tracker.process(&id, sample(MonitoredValue::Bool(true))).is_empty());
+
+    let events = tracker.process(&id, sample(MonitoredValue::Bool(false)));
+    assert_eq!(events, vec![SensorEvent::Changed{ id: id.clone(), from: true, to: false }]);
+    assert!(tracker.process(&id, sample(MonitoredValue::Bool(false))).is_empty());
+}
+
+#[test]
+fn threshold_crossings_in_both_directions() {
+    let id = SensorId::new(SensorSource::Rx(1), "temp");
+    let mut tracker = Tracker::new(8);
+    tracker.add_threshold(id.clone(), 60.0);
+
+    tracker.process(&id, sample(MonitoredValue::Number(55.0)));
+
+    let events = tracker.process(&id, sample(MonitoredValue::Number(61.5)));
+    assert_eq!(events, vec![SensorEvent::ThresholdCrossed{ id: id.clone(), threshold: 60.0, value: 61.5, crossing: Crossing::Rising }]);
+
+    assert!(tracker.process(&id, sample(MonitoredValue::Number(62.0))).is_empty());
+
+    let events = tracker.process(&id, sample(MonitoredValue::Number(59.0)));
+    assert_eq!(events, vec![SensorEvent::ThresholdCrossed{ id: id.clone(), threshold: 60.0, value: 59.0, crossing: Crossing::Falling }]);
+}
+
+#[test]
+fn first_sample_reports_initial_state() {
+    let lock = SensorId::new(SensorSource::Mboard(0), "ref_locked");
+    let temp = SensorId::new(SensorSource::Rx(0), "temp");
+    let mut tracker = Tracker::new(8);
+    tracker.add_threshold(temp.clone(), 60.0);
+    tracker.add_threshold(temp.clone(), 80.0);
+
+    let events = tracker.process(&lock, sample(MonitoredValue::Bool(false)));
+    assert_eq!(events, vec![SensorEvent::Initial{ id: lock.clone(), value: MonitoredValue::Bool(false), above: vec![] }]);
+    let events = tracker.process(&temp, sample(MonitoredValue::Number(60.0)));
+    assert_eq!(events, vec![SensorEvent::Initial{ id: temp.clone(), value: MonitoredValue::Number(60.0), above: vec![60.0] }]);
+
+    // Falling below a threshold the sensor started above is reported as usual
+    let events = tracker.process(&temp, sample(MonitoredValue::Number(20.0)));
+    assert_eq!(events, vec![SensorEvent::ThresholdCrossed{ id: temp.clone(), threshold: 60.0, value: 20.0, crossing: Crossing::Falling }]);
+}
+
+#[test]
+fn history_is_bounded() {
+    let id = SensorId::new(SensorSource::Tx(0), "rssi");
+    let mut tracker = Tracker::new(3);
+    for x in 0..5 {
+        tracker.process(&id, sample(MonitoredValue::Number(x as f64)));
+    }
+
+    let values:Vec<MonitoredValue> = tracker.history(&id).into_iter().map(|s| s.value).collect();
+    assert_eq!(values, vec![MonitoredValue::Number(2.0), MonitoredValue::Number(3.0), MonitoredValue::Number(4.0)]);
+    assert_eq!(Some(MonitoredValue::Number(4.0)), tracker.latest(&id).map(|s| s.value));
+    assert!(tracker.history(&SensorId::new(SensorSource::Tx(1), "rssi")).is_empty());
+}
@@ -0,0 +1,32 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID:AtomicUsize = AtomicUsize::new(0);
+
+// Scratch directory for a test, removed when dropped so a failing assert doesn't leave files behind
+pub struct TempDir {
+    path:PathBuf,
+}
+
+impl TempDir {
+
+    pub fn new(name:&str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("uhd_rs_{}_{}_{}", name, std::process::id(), id));
+        std::fs::create_dir_all(&path).unwrap();
+        Self{ path }
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    pub fn join<P: AsRef<Path>>(&self, name:P) -> PathBuf { self.path.join(name) }
+
+}
+
+impl Drop for TempDir {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+
+}
@@ -1,6 +1,59 @@
 use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::types::TimeSpec;
+use crate::types::sensors::Sensor;
 use crate::usrp::USRP;
 
+pub mod time_sync;
+
+#[cfg(test)]
+mod tests;
+
+const PPS_TIMEOUT:Duration = Duration::from_millis(1500);
+const PPS_POLL_INTERVAL:Duration = Duration::from_millis(10);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignmentReport {
+    pub aligned:bool,
+    pub attempts:usize,
+    pub last_pps:Vec<TimeSpec>,     // Time of the last PPS on each mboard after alignment
+    pub misaligned:Vec<Vec<TimeSpec>>,  // Last PPS on each mboard after every attempt that didn't line up
+}
+
+// Sets the time on every mboard on the same PPS edge so that they all read `full_secs` at the following edge,
+// then checks that they agree.  This assumes the mboards already share a PPS, e.g. through `time_sync::TimeSync`.
+// Running out of attempts isn't an error; the report comes back with `aligned` unset and every attempt's PPS times.
+pub fn align_all_mboards(usrp: &mut USRP, full_secs: i64, max_attempts: usize) -> Result<AlignmentReport, &'static str> {
+
+    let num_mboards = usrp.num_mboards()?;
+    let mut misaligned = vec![];
+
+    for attempt in 1..=max_attempts {
+
+        // Issue the commands right after an edge so they all land well before the next one
+        time_sync::wait_for_pps_edge(usrp, 0, PPS_TIMEOUT, PPS_POLL_INTERVAL)?;
+        for mboard in 0..num_mboards {
+            usrp.set_time_next_pps(full_secs, 0.0, mboard)?;
+        }
+        time_sync::wait_for_pps_edge(usrp, 0, PPS_TIMEOUT, PPS_POLL_INTERVAL)?;
+
+        let mut last_pps = vec![];
+        for mboard in 0..num_mboards {
+            last_pps.push(usrp.get_time_last_pps(mboard)?);
+        }
+
+        if usrp.get_time_synchronized()? && last_pps.iter().all(|t| t.0 == full_secs) {
+            return Ok(AlignmentReport{ aligned: true, attempts: attempt, last_pps, misaligned });
+        }
+
+        misaligned.push(last_pps);
+    }
+
+    let last_pps = misaligned.last().cloned().unwrap_or_default();
+    Ok(AlignmentReport{ aligned: false, attempts: max_attempts, last_pps, misaligned })
+}
+
 pub fn sync_to_gps(usrp: &mut USRP, print_status: bool) -> Result<(), &'static str> {
 
     let time_sources:HashSet<String> = usrp.get_time_sources(0)?.into_iter().collect();
@@ -11,7 +64,7 @@ pub fn sync_to_gps(usrp: &mut USRP, print_status: bool) -> Result<(), &'static s
         return Err("Synching to GPS requires a GPSDO");
     }
 
-    if !sensor_names.contains("ref_locked") | !sensor_names.contains("gps_locked") {
+    if !sensor_names.contains(Sensor::RefLocked.name()) | !sensor_names.contains(Sensor::GpsLocked.name()) {
         return Err("Sensors expected to include ref_locked and gps_locked");
     }
 
@@ -23,8 +76,8 @@ pub fn sync_to_gps(usrp: &mut USRP, print_status: bool) -> Result<(), &'static s
     }
 
     for _ in 0..30 {
-        let ref_locked:bool = usrp.get_mboard_sensor("ref_locked", 0)?.to_bool()?;
-        let gps_locked:bool = usrp.get_mboard_sensor("gps_locked", 0)?.to_bool()?;
+        let ref_locked:bool = usrp.get_mboard_sensor(Sensor::RefLocked, 0)?.to_bool()?;
+        let gps_locked:bool = usrp.get_mboard_sensor(Sensor::GpsLocked, 0)?.to_bool()?;
         if ref_locked && gps_locked {
             break;
         } else {
@@ -38,14 +91,14 @@ pub fn sync_to_gps(usrp: &mut USRP, print_status: bool) -> Result<(), &'static s
     // Set to GPS time
     // Note: this isn't GPS time-of-week; it's UTC time
     // provided by GPS
-    let gps_time = usrp.get_mboard_sensor("gps_time", 0)?.to_int()?;
+    let gps_time = usrp.get_mboard_sensor(Sensor::GpsTime, 0)?.to_int()?;
     usrp.set_time_next_pps(gps_time as i64 + 1, 0.0, 0)?;
 
     // Wait for it to apply
     std::thread::sleep(std::time::Duration::from_secs(2));
 
     // Check times
-    let gps_time = usrp.get_mboard_sensor("gps_time", 0)?.to_int()?;
+    let gps_time = usrp.get_mboard_sensor(Sensor::GpsTime, 0)?.to_int()?;
     let time_last_pps = usrp.get_time_last_pps(0)?;
 
     if print_status {
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use crate::timing::time_sync::{time_add, time_diff, MboardSyncReport, SyncSource, TimeSyncReport};
+
+#[test]
+fn mimo_source_names() {
+    assert_eq!("internal", SyncSource::Mimo.source_name(0));
+    assert_eq!("mimo", SyncSource::Mimo.source_name(1));
+    assert!(SyncSource::Mimo.lock_sensors(0).is_empty());
+    assert_eq!(vec!["mimo_locked"], SyncSource::Mimo.lock_sensors(1));
+    assert_eq!(vec!["ref_locked", "gps_locked"], SyncSource::Gpsdo.lock_sensors(3));
+    assert_eq!(Ok(SyncSource::External), "external".parse());
+    assert!("pps".parse::<SyncSource>().is_err());
+}
+
+#[test]
+fn time_differences() {
+    assert_eq!(0.5, time_diff((10, 0.75), (10, 0.25)));
+    assert_eq!(-0.75, time_diff((9, 0.5), (10, 0.25)));
+}
+
+#[test]
+fn time_addition() {
+    assert_eq!((11, 0.25), time_add((10, 0.75), 0.5));
+    assert_eq!((9, 0.5), time_add((10, 0.25), -0.75));
+    assert_eq!((10, 0.5), time_add((10, 0.5), 0.0));
+}
+
+#[test]
+fn report_residuals() {
+    let mboard = |mboard:usize, residual_secs:f64| MboardSyncReport{
+        mboard, source: "external".to_owned(), lock_time: Some(Duration::from_millis(20)), time_set: 1, residual_secs
+    };
+
+    let report = TimeSyncReport{ source: SyncSource::External, pps_wait: None, mboards: vec![mboard(0, 0.0), mboard(1, -1.0)] };
+    assert_eq!(1.0, report.max_abs_residual());
+    assert!(!report.is_synchronized(1.0e-6));
+    assert!(report.is_synchronized(1.0));
+
+    let empty = TimeSyncReport{ source: SyncSource::External, pps_wait: None, mboards: vec![] };
+    assert!(!empty.is_synchronized(1.0));
+}
@@ -0,0 +1,260 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::types::TimeSpec;
+use crate::types::sensors::Sensor;
+use crate::usrp::USRP;
+
+const MIMO_LOCKED:&str = "mimo_locked";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncSource {
+    Internal,   // Each mboard runs from its own oscillator; time is set immediately
+    External,   // 10 MHz and PPS on the reference inputs of every mboard
+    Gpsdo,      // Each mboard disciplined by its own GPSDO and set to GPS-provided UTC
+    Mimo,       // Mboard 0 runs internally and the rest follow it over the MIMO cable
+}
+
+impl SyncSource {
+
+    // Clock and time source names passed to UHD for a given mboard
+    pub fn source_name(&self, mboard:usize) -> &'static str {
+        match self {
+            SyncSource::Internal => "internal",
+            SyncSource::External => "external",
+            SyncSource::Gpsdo    => "gpsdo",
+            SyncSource::Mimo if mboard == 0 => "internal",
+            SyncSource::Mimo     => "mimo",
+        }
+    }
+
+    // Sensors that need to read true before the reference on a given mboard can be trusted
+    pub fn lock_sensors(&self, mboard:usize) -> Vec<&'static str> {
+        match self {
+            SyncSource::Internal => vec![],
+            SyncSource::External => vec![Sensor::RefLocked.name()],
+            SyncSource::Gpsdo    => vec![Sensor::RefLocked.name(), Sensor::GpsLocked.name()],
+            SyncSource::Mimo if mboard == 0 => vec![],
+            SyncSource::Mimo     => vec![MIMO_LOCKED],
+        }
+    }
+
+    fn uses_pps(&self) -> bool {
+        *self != SyncSource::Internal
+    }
+
+}
+
+impl std::str::FromStr for SyncSource {
+    type Err = &'static str;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        match s {
+            "internal" => Ok(SyncSource::Internal),
+            "external" => Ok(SyncSource::External),
+            "gpsdo"    => Ok(SyncSource::Gpsdo),
+            "mimo"     => Ok(SyncSource::Mimo),
+            _ => Err("Time sync source must be one of internal, external, gpsdo or mimo"),
+        }
+    }
+}
+
+// What the device time is set to for sources without their own notion of absolute time.  GPSDO sync always
+// uses the time reported by the GPS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeBase {
+    Zero,
+    HostClock,
+    Fixed(i64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MboardSyncReport {
+    pub mboard:usize,
+    pub source:String,
+    pub lock_time:Option<Duration>,     // None if the mboard has none of the expected lock sensors
+    pub time_set:i64,                   // Full seconds the mboard was told to take on
+    pub residual_secs:f64,              // Reported time minus expected time, read back after the sync
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeSyncReport {
+    pub source:SyncSource,
+    pub pps_wait:Option<Duration>,      // Time spent waiting for the PPS edge that the time was set on
+    pub mboards:Vec<MboardSyncReport>,
+}
+
+impl TimeSyncReport {
+
+    pub fn max_abs_residual(&self) -> f64 {
+        self.mboards.iter().map(|m| m.residual_secs.abs()).fold(0.0, f64::max)
+    }
+
+    pub fn is_synchronized(&self, tolerance_secs:f64) -> bool {
+        !self.mboards.is_empty() && self.max_abs_residual() <= tolerance_secs
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeSync {
+    pub source:SyncSource,
+    pub time_base:TimeBase,
+    pub lock_timeout:Duration,
+    pub pps_timeout:Duration,
+    pub poll_interval:Duration,
+    pub print_status:bool,
+}
+
+impl TimeSync {
+
+    pub fn new(source:SyncSource) -> Self {
+        Self {
+            source, time_base: TimeBase::Zero,
+            lock_timeout: Duration::from_secs(30),
+            pps_timeout: Duration::from_millis(1500),
+            poll_interval: Duration::from_millis(10),
+            print_status: false,
+        }
+    }
+
+    pub fn time_base(mut self, time_base:TimeBase) -> Self { self.time_base = time_base; self }
+    pub fn lock_timeout(mut self, timeout:Duration) -> Self { self.lock_timeout = timeout; self }
+    pub fn pps_timeout(mut self, timeout:Duration) -> Self { self.pps_timeout = timeout; self }
+    pub fn poll_interval(mut self, interval:Duration) -> Self { self.poll_interval = interval; self }
+    pub fn print_status(mut self, print_status:bool) -> Self { self.print_status = print_status; self }
+
+    pub fn run(&self, usrp:&mut USRP) -> Result<TimeSyncReport, &'static str> {
+        let num_mboards = usrp.num_mboards()?;
+
+        for mboard in 0..num_mboards {
+            let name = self.source.source_name(mboard);
+            if !usrp.get_clock_sources(mboard)?.iter().any(|s| s == name) || !usrp.get_time_sources(mboard)?.iter().any(|s| s == name) {
+                return Err("Requested time sync source isn't available on every mboard");
+            }
+            usrp.set_clock_source(name, mboard)?;
+            usrp.set_time_source(name, mboard)?;
+        }
+
+        let mut lock_times = vec![];
+        for mboard in 0..num_mboards {
+            lock_times.push(self.wait_for_lock(usrp, mboard)?);
+        }
+
+        let mut report = TimeSyncReport{ source: self.source, pps_wait: None, mboards: vec![] };
+
+        if !self.source.uses_pps() {
+            // Without a shared PPS the best available check is how far each mboard has drifted from the host
+            // clock since it was set, which includes the latency of the calls themselves
+            let t = self.base_secs();
+            let mut set_at = vec![];
+            for mboard in 0..num_mboards {
+                usrp.set_time_now(t, 0.0, mboard)?;
+                set_at.push(Instant::now());
+            }
+            for (mboard, (lock_time, set_at)) in lock_times.into_iter().zip(set_at).enumerate() {
+                let now = usrp.get_time_now(mboard)?;
+                let residual = time_diff(now, (t, set_at.elapsed().as_secs_f64()));
+                report.mboards.push(self.mboard_report(mboard, lock_time, t, residual));
+            }
+            return Ok(report);
+        }
+
+        // Set the time right after an edge so there's nearly a full second to get the commands to every mboard
+        let (_, pps_wait) = wait_for_pps_edge(usrp, 0, self.pps_timeout, self.poll_interval)?;
+        report.pps_wait = Some(pps_wait);
+
+        let mut targets = vec![];
+        for mboard in 0..num_mboards {
+            let t = match self.source {
+                SyncSource::Gpsdo => usrp.get_mboard_sensor(Sensor::GpsTime, mboard)?.to_int()? as i64,
+                _ => self.base_secs(),
+            };
+            usrp.set_time_next_pps(t + 1, 0.0, mboard)?;
+            targets.push(t + 1);
+        }
+
+        wait_for_pps_edge(usrp, 0, self.pps_timeout, self.poll_interval)?;
+
+        for (mboard, (lock_time, target)) in lock_times.into_iter().zip(targets).enumerate() {
+            let last_pps = usrp.get_time_last_pps(mboard)?;
+            let residual = time_diff(last_pps, (target, 0.0));
+            if self.print_status {
+                println!("Mboard {}: time set to {}, last PPS reads {:?}", mboard, target, last_pps);
+            }
+            report.mboards.push(self.mboard_report(mboard, lock_time, target, residual));
+        }
+
+        Ok(report)
+    }
+
+    fn mboard_report(&self, mboard:usize, lock_time:Option<Duration>, time_set:i64, residual_secs:f64) -> MboardSyncReport {
+        MboardSyncReport{ mboard, source: self.source.source_name(mboard).to_owned(), lock_time, time_set, residual_secs }
+    }
+
+    fn base_secs(&self) -> i64 {
+        match self.time_base {
+            TimeBase::Zero => 0,
+            TimeBase::Fixed(t) => t,
+            TimeBase::HostClock => SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0),
+        }
+    }
+
+    fn wait_for_lock(&self, usrp:&USRP, mboard:usize) -> Result<Option<Duration>, &'static str> {
+        let available = usrp.get_mboard_sensor_names(mboard)?;
+        let sensors:Vec<&str> = self.source.lock_sensors(mboard).into_iter()
+            .filter(|s| available.iter().any(|a| a == s))
+            .collect();
+
+        if sensors.is_empty() {
+            return Ok(None);
+        }
+
+        if self.print_status {
+            println!("Mboard {}: waiting for {:?}...", mboard, sensors);
+        }
+
+        let t0 = Instant::now();
+        loop {
+            let mut all_locked = true;
+            for sensor in sensors.iter() {
+                all_locked &= usrp.get_mboard_sensor(sensor, mboard)?.to_bool()?;
+            }
+
+            if all_locked {
+                return Ok(Some(t0.elapsed()));
+            } else if t0.elapsed() > self.lock_timeout {
+                return Err("Timed out waiting for reference lock");
+            }
+
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+
+}
+
+// Difference a - b between two time specs in seconds
+pub fn time_diff(a:TimeSpec, b:TimeSpec) -> f64 {
+    (a.0 - b.0) as f64 + (a.1 - b.1)
+}
+
+// Time spec `secs` seconds after `t`, with the fractional part kept in [0, 1)
+pub fn time_add(t:TimeSpec, secs:f64) -> TimeSpec {
+    let frac = t.1 + secs;
+    let whole = frac.floor();
+    (t.0 + whole as i64, frac - whole)
+}
+
+// Polls the time of the last PPS until it changes and returns the new value along with how long that took
+pub fn wait_for_pps_edge(usrp:&USRP, mboard:usize, timeout:Duration, poll_interval:Duration) -> Result<(TimeSpec, Duration), &'static str> {
+    let t0 = Instant::now();
+    let initial = usrp.get_time_last_pps(mboard)?;
+    loop {
+        let current = usrp.get_time_last_pps(mboard)?;
+        if current != initial {
+            return Ok((current, t0.elapsed()));
+        } else if t0.elapsed() > timeout {
+            return Err("Timed out waiting for a PPS edge");
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
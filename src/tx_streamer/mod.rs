@@ -5,7 +5,8 @@ use std::io::{Error, ErrorKind};
 use libc::{c_char, size_t};
 
 use crate::check_err;
-use crate::types::metadata::TxMetadata;
+use crate::convert;
+use crate::types::metadata::{AsyncMetadata, TxMetadata};
 
 type Sample = (i16, i16);
 
@@ -13,12 +14,12 @@ type Sample = (i16, i16);
 extern {
 	
 	// uhd_error uhd_tx_streamer_num_channels(uhd_tx_streamer_handle h, size_t *num_channels_out)
-	// uhd_error uhd_tx_streamer_recv_async_msg(uhd_tx_streamer_handle h, uhd_async_metadata_handle *md, double timeout, bool *valid)
 
 	fn uhd_tx_streamer_make(h: &mut usize) -> isize;
 	fn uhd_tx_streamer_free(h: &mut usize) -> isize;
 	fn uhd_tx_streamer_max_num_samps(h:usize, max_num_samps_out:&mut size_t) -> isize;
 	fn uhd_tx_streamer_send(h:usize, buffs:&*const u8, samps_per_buff:size_t, md:&usize, timeout:f64, items_sent:&mut size_t) -> isize;
+	fn uhd_tx_streamer_recv_async_msg(h:usize, md:&mut usize, timeout:f64, valid:&mut bool) -> isize;
 	fn uhd_tx_streamer_last_error(h:usize, error_out:*const c_char, strbuffer_len:size_t) -> isize;
 
 }
@@ -33,23 +34,12 @@ pub struct TxStreamer {
 
 impl std::io::Write for TxStreamer {
 
+	// Bytes are interleaved little-endian sc16, like the files `io::write_sc16_to_file` writes. They're sent in place
+	// when the host's byte order and the buffer's alignment allow it and copied otherwise.
 	fn write(&mut self, buffer:&[u8]) -> Result<usize, std::io::Error> { 
-		let bytes_per_sample:usize = std::mem::size_of::<Sample>();
-		let num_bytes:usize = buffer.len();
-
-		if num_bytes % bytes_per_sample == 0 {
-
-			let num_samples:usize = num_bytes / bytes_per_sample;
-
-			let wf_u:*const u8 = &buffer[0];
-			let wf_s:*const Sample = wf_u as *const _;
-			let samp_buffer:&[Sample] = unsafe { std::slice::from_raw_parts(wf_s, num_samples) };
-
-			self.single_coherent_pulse(samp_buffer, None).map_err(|e| Error::new(ErrorKind::Interrupted, e))
-
-		} else {
-			Err(Error::new(ErrorKind::Interrupted, "Wrong sized input for write()"))
-		}
+		let samp_buffer = convert::iq_from_le_bytes::<i16>(buffer).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+		let samples_sent = self.single_coherent_pulse(&samp_buffer, None).map_err(Error::other)?;
+		Ok(samples_sent * std::mem::size_of::<Sample>())
 	}
 
 	fn flush(&mut self) -> Result<(), std::io::Error> { 
@@ -81,72 +71,76 @@ impl TxStreamer {
 		}
 	}
 
+	// Sends block for up to this long waiting for room in the device's buffer, which includes waiting for a timed
+	// start to come around
+	pub fn set_timeout(&mut self, timeout:f64) { self.timeout = timeout; }
+
+	// Waits up to `timeout` seconds for an event about transmitted samples and returns whether `md` was filled in
+	pub fn recv_async_msg(&mut self, md:&mut AsyncMetadata, timeout:f64) -> Result<bool, &'static str> {
+		let mut valid = false;
+		match unsafe { uhd_tx_streamer_recv_async_msg(self.handle, &mut md.handle, timeout, &mut valid) } {
+			0 => Ok(valid),
+			_ => Err("Unable to receive async message from TxStreamer")
+		}
+	}
+
 	pub fn single_coherent_pulse(&mut self, buffer:&[Sample], time_spec:Option<(i64, f64)>) -> Result<usize, &'static str> {
 		// The burst boundaries seem to tell UHD that phase coherence
 		// isn't required in between bursts
-		let md0 = TxMetadata::new(time_spec, true,  false)?;
-		let md1 = TxMetadata::new(None,      false, false)?;
-		let md2 = TxMetadata::new(None,      false, true )?;
-		self.send_sc16(buffer, &md0, &md1, &md2)
+		self.send_sc16(buffer, time_spec, true, true)
 	}
 
 	pub fn start_coherent(&mut self, buffer:&[Sample], time_spec:Option<(i64, f64)>) -> Result<usize, &'static str> {
-		let md0 = TxMetadata::new(time_spec, true,  false)?;
-		let md1 = TxMetadata::new(None,      false, false)?;
-		self.send_sc16(buffer, &md0, &md1, &md1)
+		self.send_sc16(buffer, time_spec, true, false)
 	}
 
 	pub fn continue_coherent(&mut self, buffer:&[Sample]) -> Result<usize, &'static str> {
-		let md = TxMetadata::new(None, false, false)?;
-		self.send_sc16(buffer, &md, &md, &md)
+		self.send_sc16(buffer, None, false, false)
 	}
 
+	// An empty buffer just ends the burst
 	pub fn complete_coherent(&mut self, buffer:&[Sample]) -> Result<usize, &'static str> {
-		let md1 = TxMetadata::new(None,      false, false)?;
-		let md2 = TxMetadata::new(None,      false, true )?;
-		self.send_sc16(buffer, &md1, &md1, &md2)
+		self.send_sc16(buffer, None, false, true)
 	}
 
 	pub fn start_at_time(&mut self, buffer:&[Sample], time_spec: (i64, f64)) -> Result<usize, &'static str> {
-		let md0 = TxMetadata::new(Some(time_spec), true, false)?;
-		let md1 = TxMetadata::new(None, false, false)?;
-		self.send_sc16(buffer, &md0, &md1, &md1)
+		self.send_sc16(buffer, Some(time_spec), true, false)
 	}
 
 	pub fn send_asap(&mut self, buffer:&[Sample]) -> Result<usize, &'static str> {
-		let md = TxMetadata::new(None, false, false)?;
-		self.send_sc16(buffer, &md, &md, &md)
+		self.send_sc16(buffer, None, false, false)
 	}
 
-	fn send_sc16(&mut self, buffer:&[Sample], md0:&TxMetadata, md1:&TxMetadata, md2:&TxMetadata) -> Result<usize, &'static str> {
+	// The time spec and start of burst go on the first packet and the end of burst on the last, which is the same
+	// packet when the buffer fits in one.  An empty buffer still goes out as one packet if it carries a flag.
+	fn send_sc16(&mut self, buffer:&[Sample], time_spec:Option<(i64, f64)>, start_of_burst:bool, end_of_burst:bool) -> Result<usize, &'static str> {
+
+		if buffer.is_empty() && !start_of_burst && !end_of_burst {
+			return Ok(0);
+		}
 
 		let mut items_sent:usize = 0;
 
-		while items_sent < buffer.len() {
+		loop {
 
 			let num_samps:usize = std::cmp::min(self.max_num_samps, buffer.len() - items_sent);
-			let metadata_handle_ref:&usize = if items_sent == 0 {
-				// First call
-				&md0.handle
-			} else if buffer.len() - items_sent > num_samps {
-				// One of the calls in the middle
-				&md1.handle
-			} else {
-				// Last call
-				&md2.handle
-			};
+			let first = items_sent == 0;
+			let last = items_sent + num_samps == buffer.len();
+			let md = TxMetadata::new(time_spec.filter(|_| first), first && start_of_burst, last && end_of_burst)?;
 
-			let start_ptr:*const (i16, i16) = &buffer[items_sent];
-			let buff_ptr:*const u8 = start_ptr as *const u8;
+			let buff_ptr:*const u8 = convert::iq_bytes(&buffer[items_sent..])?.as_ptr();
 			let mut items_sent_this_time = 0;
 			let result = unsafe { 
 				uhd_tx_streamer_send(self.handle, &buff_ptr, num_samps, 
-					metadata_handle_ref, self.timeout, &mut items_sent_this_time) 
+					&md.handle, self.timeout, &mut items_sent_this_time) 
 			};
 
 			check_err((), result)?;
 
-			items_sent += items_sent_this_time
+			items_sent += items_sent_this_time;
+			if items_sent >= buffer.len() {
+				break;
+			}
 
 		}
 
@@ -0,0 +1,157 @@
+use std::ffi::CString;
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+// Keys UHD understands for device addresses and stream args across the supported device families.  Anything else
+// is passed through untouched but flagged by `DeviceArgs::validate`.
+pub const KNOWN_KEYS:&[&str] = &[
+    // Device selection
+    "type", "product", "serial", "name", "addr", "second_addr", "mgmt_addr", "resource", "fpga", "claimed",
+    // Device setup
+    "master_clock_rate", "clock_source", "time_source", "dboard_clock_rate", "skip_init", "use_dpdk",
+    // Transport
+    "num_recv_frames", "num_send_frames", "recv_frame_size", "send_frame_size", "recv_buff_size", "send_buff_size",
+    // Stream args
+    "spp", "fullscale", "peak", "underflow_policy",
+];
+
+// UHD's `key=value,key=value` argument syntax, with keys kept in the order they were added
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceArgs {
+    pairs:Vec<(String, String)>,
+}
+
+impl DeviceArgs {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Used for the `From` conversions, which can't fail; tokens without an `=` are kept as keys with an empty value
+    fn parse_lenient(s:&str) -> Self {
+        s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()).fold(Self::new(), |args, token| {
+            match token.split_once('=') {
+                Some((key, value)) => args.set(key.trim(), value.trim()),
+                None => args.set(token, ""),
+            }
+        })
+    }
+
+    pub fn set<K: AsRef<str>, V: ToString>(mut self, key:K, value:V) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    // Replaces the value if the key is already present, otherwise appends it
+    pub fn insert<K: AsRef<str>, V: ToString>(&mut self, key:K, value:V) {
+        let value = value.to_string();
+        match self.pairs.iter_mut().find(|(k, _)| k == key.as_ref()) {
+            Some(pair) => pair.1 = value,
+            None => self.pairs.push((key.as_ref().to_owned(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key:&str) -> Option<String> {
+        let idx = self.pairs.iter().position(|(k, _)| k == key)?;
+        Some(self.pairs.remove(idx).1)
+    }
+
+    pub fn get(&self, key:&str) -> Option<&str> {
+        self.pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains_key(&self, key:&str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn device_type(self, device_type:&str) -> Self { self.set("type", device_type) }
+    pub fn serial(self, serial:&str) -> Self { self.set("serial", serial) }
+    pub fn addr(self, addr:&str) -> Self { self.set("addr", addr) }
+    pub fn name(self, name:&str) -> Self { self.set("name", name) }
+    pub fn master_clock_rate(self, rate:f64) -> Self { self.set("master_clock_rate", rate) }
+    pub fn num_recv_frames(self, frames:usize) -> Self { self.set("num_recv_frames", frames) }
+    pub fn num_send_frames(self, frames:usize) -> Self { self.set("num_send_frames", frames) }
+    pub fn recv_buff_size(self, bytes:usize) -> Self { self.set("recv_buff_size", bytes) }
+    pub fn send_buff_size(self, bytes:usize) -> Self { self.set("send_buff_size", bytes) }
+
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        self.pairs.iter().map(|(k, _)| k.as_str()).filter(|k| !KNOWN_KEYS.contains(k)).collect()
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !self.unknown_keys().is_empty() {
+            return Err("Device args contain an unknown key");
+        }
+
+        if self.get("master_clock_rate").map(|v| v.parse::<f64>().is_err()).unwrap_or(false) {
+            return Err("Device arg `master_clock_rate` must be a number");
+        }
+        for key in ["num_recv_frames", "num_send_frames", "recv_frame_size", "send_frame_size", "recv_buff_size", "send_buff_size"] {
+            if self.get(key).map(|v| v.parse::<usize>().is_err()).unwrap_or(false) {
+                return Err("Device arg frame counts and sizes must be non-negative integers");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_cstring(&self) -> Result<CString, &'static str> {
+        CString::new(self.to_string()).map_err(|_| "Unable to create CString; check for null characters")
+    }
+
+}
+
+impl fmt::Display for DeviceArgs {
+
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        let tokens:Vec<String> = self.pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "{}", tokens.join(","))
+    }
+
+}
+
+// Strict parse; every token has to be a `key=value` pair with a non-empty key
+impl std::str::FromStr for DeviceArgs {
+    type Err = &'static str;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        let mut ans = Self::new();
+        for token in s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            match token.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => ans.insert(key.trim(), value.trim()),
+                _ => return Err("Device args must be comma-separated key=value pairs"),
+            }
+        }
+        Ok(ans)
+    }
+}
+
+impl From<&str> for DeviceArgs {
+    fn from(s:&str) -> Self { Self::parse_lenient(s) }
+}
+
+impl From<String> for DeviceArgs {
+    fn from(s:String) -> Self { Self::parse_lenient(&s) }
+}
+
+impl From<&String> for DeviceArgs {
+    fn from(s:&String) -> Self { Self::parse_lenient(s) }
+}
+
+impl From<&DeviceArgs> for DeviceArgs {
+    fn from(args:&DeviceArgs) -> Self { args.clone() }
+}
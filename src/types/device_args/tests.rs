@@ -0,0 +1,42 @@
+use crate::types::device_args::DeviceArgs;
+
+#[test]
+fn parse_and_emit() {
+    let args:DeviceArgs = " type=b200, serial = 31A5B1C ,num_recv_frames=512".parse().unwrap();
+    assert_eq!(Some("b200"), args.get("type"));
+    assert_eq!(Some("31A5B1C"), args.get("serial"));
+    assert_eq!(3, args.len());
+    assert_eq!("type=b200,serial=31A5B1C,num_recv_frames=512", args.to_string());
+
+    assert!("".parse::<DeviceArgs>().unwrap().is_empty());
+    assert!("type=b200,serial".parse::<DeviceArgs>().is_err());
+    assert!("=b200".parse::<DeviceArgs>().is_err());
+}
+
+#[test]
+fn typed_setters() {
+    let args = DeviceArgs::new()
+        .addr("192.168.10.2")
+        .master_clock_rate(200.0e6)
+        .recv_buff_size(50_000_000)
+        .addr("192.168.10.3");
+
+    assert_eq!("addr=192.168.10.3,master_clock_rate=200000000,recv_buff_size=50000000", args.to_string());
+    assert!(args.validate().is_ok());
+}
+
+#[test]
+fn validation() {
+    let mut args = DeviceArgs::from("type=x300,num_recv_frames=many");
+    assert!(args.validate().is_err());
+
+    args.insert("num_recv_frames", 256);
+    assert!(args.validate().is_ok());
+
+    args.insert("bogus", "1");
+    assert_eq!(vec!["bogus"], args.unknown_keys());
+    assert!(args.validate().is_err());
+
+    assert_eq!(Some("1".to_owned()), args.remove("bogus"));
+    assert!(args.validate().is_ok());
+}
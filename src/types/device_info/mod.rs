@@ -0,0 +1,89 @@
+use std::net::Ipv4Addr;
+
+use crate::types::device_args::DeviceArgs;
+
+#[cfg(test)]
+mod tests;
+
+// One entry from a device discovery, with the keys UHD reports for most device families pulled out
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceInfo {
+    pub device_type:Option<String>,
+    pub product:Option<String>,
+    pub serial:Option<String>,
+    pub name:Option<String>,
+    pub addr:Option<String>,
+    pub fpga:Option<String>,
+    pub args:DeviceArgs,        // Everything the discovery returned, including keys not listed above
+}
+
+impl DeviceInfo {
+
+    pub fn from_args(args:DeviceArgs) -> Self {
+        // UHD reports keys it doesn't have a value for (like an unset name) as empty strings
+        let get = |key:&str| args.get(key).filter(|v| !v.is_empty()).map(|v| v.to_owned());
+        Self {
+            device_type: get("type"),
+            product: get("product"),
+            serial: get("serial"),
+            name: get("name"),
+            addr: get("addr"),
+            fpga: get("fpga"),
+            args
+        }
+    }
+
+    pub fn is_type(&self, device_type:&str) -> bool {
+        self.device_type.as_deref() == Some(device_type)
+    }
+
+    pub fn has_serial(&self, serial:&str) -> bool {
+        self.serial.as_deref().map(|s| s.eq_ignore_ascii_case(serial)).unwrap_or(false)
+    }
+
+    // True if the device has an IPv4 address within `network`/`prefix_len`
+    pub fn in_network(&self, network:Ipv4Addr, prefix_len:u32) -> bool {
+        let addr:Ipv4Addr = match self.addr.as_deref().and_then(|a| a.parse().ok()) {
+            Some(addr) => addr,
+            None => return false,
+        };
+        let mask = u32::MAX.checked_shl(32 - prefix_len.min(32)).unwrap_or(0);
+        u32::from(addr) & mask == u32::from(network) & mask
+    }
+
+    // Args that select this device and nothing else; the serial is unique across every device family, so it's
+    // preferred over the address or name when available
+    pub fn open_args(&self) -> DeviceArgs {
+        let mut ans = DeviceArgs::new();
+        if let Some(device_type) = &self.device_type {
+            ans.insert("type", device_type);
+        }
+        match (&self.serial, &self.addr, &self.name) {
+            (Some(serial), _, _) => ans.insert("serial", serial),
+            (None, Some(addr), _) => ans.insert("addr", addr),
+            (None, None, Some(name)) => ans.insert("name", name),
+            (None, None, None) => return self.args.clone(),
+        }
+        ans
+    }
+
+    // Orders devices the same way regardless of the order discovery returned them in
+    pub fn sort(devices:&mut [DeviceInfo]) {
+        devices.sort_by(|a, b| {
+            (&a.device_type, &a.serial, &a.addr, &a.name).cmp(&(&b.device_type, &b.serial, &b.addr, &b.name))
+        });
+    }
+
+}
+
+impl std::str::FromStr for DeviceInfo {
+    type Err = &'static str;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        Ok(Self::from_args(s.parse()?))
+    }
+}
+
+impl From<&DeviceInfo> for DeviceArgs {
+    fn from(info:&DeviceInfo) -> Self { info.open_args() }
+}
@@ -0,0 +1,43 @@
+use std::net::Ipv4Addr;
+
+use crate::types::device_info::DeviceInfo;
+
+#[test]
+fn parse_discovery_result() {
+    let info:DeviceInfo = "type=x300,addr=192.168.40.2,fpga=HG,name=,serial=30C8B2A,product=X310".parse().unwrap();
+    assert!(info.is_type("x300"));
+    assert_eq!(Some("X310"), info.product.as_deref());
+    assert_eq!(Some("HG"), info.fpga.as_deref());
+    assert_eq!(None, info.name);
+    assert!(info.has_serial("30c8b2a"));
+    assert_eq!("type=x300,serial=30C8B2A", info.open_args().to_string());
+}
+
+#[test]
+fn open_args_fallbacks() {
+    let info:DeviceInfo = "type=usrp2,addr=192.168.10.2".parse().unwrap();
+    assert_eq!("type=usrp2,addr=192.168.10.2", info.open_args().to_string());
+
+    let info:DeviceInfo = "resource=RIO0".parse().unwrap();
+    assert_eq!("resource=RIO0", info.open_args().to_string());
+}
+
+#[test]
+fn network_filter() {
+    let info:DeviceInfo = "type=n3xx,addr=192.168.20.2".parse().unwrap();
+    assert!(info.in_network(Ipv4Addr::new(192, 168, 20, 0), 24));
+    assert!(!info.in_network(Ipv4Addr::new(192, 168, 10, 0), 24));
+    assert!(info.in_network(Ipv4Addr::new(10, 0, 0, 0), 0));
+
+    let usb:DeviceInfo = "type=b200,serial=31A5B1C".parse().unwrap();
+    assert!(!usb.in_network(Ipv4Addr::new(0, 0, 0, 0), 0));
+}
+
+#[test]
+fn deterministic_order() {
+    let mut devices:Vec<DeviceInfo> = ["type=b200,serial=B", "type=b200,serial=A", "type=x300,serial=0"]
+        .iter().map(|s| s.parse().unwrap()).collect();
+    DeviceInfo::sort(&mut devices);
+    let serials:Vec<&str> = devices.iter().filter_map(|d| d.serial.as_deref()).collect();
+    assert_eq!(vec!["A", "B", "0"], serials);
+}
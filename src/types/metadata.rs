@@ -44,19 +44,32 @@ extern {
 	// uhd_error uhd_tx_metadata_start_of_burst(uhd_tx_metadata_handle h, bool *result_out)
 	// uhd_error uhd_tx_metadata_end_of_burst(uhd_tx_metadata_handle h, bool *result_out)
 	// uhd_error uhd_tx_metadata_last_error(uhd_tx_metadata_handle h, char* error_out, size_t strbuffer_len)
+
 	// uhd_error uhd_async_metadata_make(uhd_async_metadata_handle* handle)
+	pub fn uhd_async_metadata_make(handle:&mut usize) -> isize;
+
 	// uhd_error uhd_async_metadata_free(uhd_async_metadata_handle* handle)
+	pub fn uhd_async_metadata_free(handle:&mut usize) -> isize;
+
 	// uhd_async_metadata_channel(uhd_async_metadata_handle h,size_t *channel_out)
+	pub fn uhd_async_metadata_channel(h:usize, channel_out:&mut usize) -> isize;
+
 	// uhd_error uhd_async_metadata_has_time_spec(uhd_async_metadata_handle h, bool *result_out)
+	pub fn uhd_async_metadata_has_time_spec(h:usize, result_out:&mut bool) -> isize;
+
 	// uhd_error uhd_async_metadata_time_spec(uhd_async_metadata_handle h, int64_t *full_secs_out, double *frac_secs_out)
+	pub fn uhd_async_metadata_time_spec(h:usize, full_secs_out:&mut i64, frac_secs_out:&mut f64) -> isize;
+
 	// uhd_error uhd_async_metadata_event_code(uhd_async_metadata_handle h, uhd_async_metadata_event_code_t *event_code_out)
+	pub fn uhd_async_metadata_event_code(h:usize, event_code_out:&mut AsyncMetadataEventCode) -> isize;
+
 	// uhd_error uhd_async_metadata_user_payload(uhd_async_metadata_handle h, uint32_t user_payload_out[4])
 	// uhd_error uhd_async_metadata_last_error(uhd_async_metadata_handle h, char* error_out, size_t strbuffer_len)
 
 }
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RxMetadataErrorCode {
     None 		= 0x0,		// No error code associated with this metadata
     Timeout    	= 0x1,		// No packet received, implementation timed out
@@ -67,6 +80,18 @@ pub enum RxMetadataErrorCode {
     BadPacket   = 0xF		// The packet could not be parsed
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AsyncMetadataEventCode {
+    BurstAck          = 0x1,	// A burst was successfully transmitted
+    Underflow         = 0x2,	// An internal send buffer has emptied
+    SeqError          = 0x4,	// Packet loss between host and device
+    TimeError         = 0x8,	// Packet had time that was late
+    UnderflowInPacket = 0x10,	// Underflow occurred inside a packet
+    SeqErrorInBurst   = 0x20,	// Packet loss within a burst
+    UserPayload       = 0x40	// Some kind of custom user payload
+}
+
 pub struct TxMetadata {
 	pub handle:usize
 }
@@ -156,3 +181,59 @@ impl std::ops::Drop for RxMetadata {
 
 }
 
+// Filled in by `TxStreamer::recv_async_msg` with events the device reports about transmitted bursts
+pub struct AsyncMetadata {
+	pub handle:usize
+}
+
+impl AsyncMetadata {
+
+	pub fn new() -> Result<AsyncMetadata, &'static str> {
+		let mut handle:usize = 0;
+		let result = unsafe { uhd_async_metadata_make(&mut handle) };
+		check_err(AsyncMetadata{ handle }, result)
+	}
+
+	pub fn channel(&self) -> Result<usize, &'static str> {
+		let mut ans:usize = 0;
+		match unsafe { uhd_async_metadata_channel(self.handle, &mut ans) } {
+			0 => Ok(ans),
+			_ => Err("Unable to get channel from async metadata")
+		}
+	}
+
+	pub fn time_spec(&self) -> Result<Option<(i64, f64)>, &'static str> {
+		let mut has_time_spec:bool = false;
+		let mut full_secs:i64 = 0;
+		let mut frac_secs:f64 = 0.0;
+		unsafe {
+			if uhd_async_metadata_has_time_spec(self.handle, &mut has_time_spec) != 0 {
+				return Err("Unable to determine whether async metadata has a time spec");
+			}
+			if !has_time_spec {
+				return Ok(None);
+			}
+			match uhd_async_metadata_time_spec(self.handle, &mut full_secs, &mut frac_secs) {
+				0 => Ok(Some((full_secs, frac_secs))),
+				_ => Err("Unable to get time spec from async metadata")
+			}
+		}
+	}
+
+	pub fn event_code(&self) -> Result<AsyncMetadataEventCode, &'static str> {
+		let mut ans = AsyncMetadataEventCode::BurstAck;
+		match unsafe { uhd_async_metadata_event_code(self.handle, &mut ans) } {
+			0 => Ok(ans),
+			_ => Err("Unable to get event code from async metadata")
+		}
+	}
+
+}
+
+impl std::ops::Drop for AsyncMetadata {
+
+	fn drop(&mut self) {
+		unsafe { uhd_async_metadata_free(&mut self.handle); }
+	}
+
+}
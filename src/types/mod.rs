@@ -1,12 +1,21 @@
 
 use libc::c_char;
 
+pub mod device_args;
+pub mod device_info;
 pub mod metadata;
+pub mod ranges;
 pub mod sensors;
+pub mod stream_args;
 pub mod string_vector;
+pub mod tune_request;
 pub mod usrp_info;
 
+// Full and fractional seconds, the way UHD passes time specs through the C API
+pub type TimeSpec = (i64, f64);
+
 #[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TuneRequestPolicy { None = 78, Auto = 65, Manual = 77 }
 
 #[repr(C)]
@@ -20,7 +29,7 @@ pub struct TuneRequest {
 }
 
 #[repr(C)]
-#[derive(Default, Debug)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
 pub struct TuneResult {
     pub clipped_rf_freq:f64,	// Target RF frequency, clipped to be within system range
     pub target_rf_freq:f64,		// Target RF frequency, including RF FE offset
@@ -0,0 +1,154 @@
+use libc::size_t;
+use serde::{Deserialize, Serialize};
+
+use crate::c_interop::collect_cstr;
+
+// From uhd/types/ranges.h
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Range {
+    pub start:f64,  // First value
+    pub stop:f64,   // Last value
+    pub step:f64    // Step between values
+}
+
+#[link(name = "uhd")]
+extern {
+
+    fn uhd_meta_range_make(h:&mut usize) -> isize;
+    fn uhd_meta_range_free(h:&mut usize) -> isize;
+
+    fn uhd_meta_range_start(h:usize, start_out:&mut f64) -> isize;
+    fn uhd_meta_range_stop(h:usize, stop_out:&mut f64) -> isize;
+    fn uhd_meta_range_step(h:usize, step_out:&mut f64) -> isize;
+    fn uhd_meta_range_clip(h:usize, value:f64, clip_step:bool, result_out:&mut f64) -> isize;
+    fn uhd_meta_range_size(h:usize, size_out:&mut size_t) -> isize;
+    fn uhd_meta_range_push_back(h:usize, range:&Range) -> isize;
+    fn uhd_meta_range_at(h:usize, num:size_t, range_out:&mut Range) -> isize;
+    fn uhd_meta_range_to_pp_string(h:usize, pp_string_out:*mut u8, strbuffer_len:size_t) -> isize;
+
+}
+
+impl Range {
+
+    pub fn new(start:f64, stop:f64, step:f64) -> Self {
+        Self{ start, stop, step }
+    }
+
+    // Clip a value into this range, optionally snapping it down onto the step grid that starts at `start`
+    pub fn clip(&self, value:f64, clip_step:bool) -> f64 {
+        let clipped = value.max(self.start).min(self.stop);
+        if clip_step && self.step > 0.0 {
+            let n_steps = ((clipped - self.start) / self.step + 1.0e-9).floor();
+            self.start + n_steps * self.step
+        } else {
+            clipped
+        }
+    }
+
+}
+
+pub struct MetaRange {
+    pub handle:usize
+}
+
+impl MetaRange {
+
+    pub fn new() -> Result<Self, &'static str> {
+        let mut handle:usize = 0;
+        match unsafe { uhd_meta_range_make(&mut handle) } {
+            0 => Ok(Self{ handle }),
+            _ => Err("Unable to create meta_range")
+        }
+    }
+
+    pub fn start(&self) -> Result<f64, &'static str> {
+        let mut ans:f64 = 0.0;
+        match unsafe { uhd_meta_range_start(self.handle, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get meta_range start")
+        }
+    }
+
+    pub fn stop(&self) -> Result<f64, &'static str> {
+        let mut ans:f64 = 0.0;
+        match unsafe { uhd_meta_range_stop(self.handle, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get meta_range stop")
+        }
+    }
+
+    pub fn step(&self) -> Result<f64, &'static str> {
+        let mut ans:f64 = 0.0;
+        match unsafe { uhd_meta_range_step(self.handle, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get meta_range step")
+        }
+    }
+
+    // Overall start, stop and step of the meta range collapsed into a single range
+    pub fn overall(&self) -> Result<Range, &'static str> {
+        Ok(Range::new(self.start()?, self.stop()?, self.step()?))
+    }
+
+    pub fn clip(&self, value:f64, clip_step:bool) -> Result<f64, &'static str> {
+        let mut ans:f64 = 0.0;
+        match unsafe { uhd_meta_range_clip(self.handle, value, clip_step, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to clip value to meta_range")
+        }
+    }
+
+    pub fn len(&self) -> Result<usize, &'static str> {
+        let mut ans:usize = 0;
+        match unsafe { uhd_meta_range_size(self.handle, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get meta_range size")
+        }
+    }
+
+    pub fn is_empty(&self) -> Result<bool, &'static str> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn push_back(&mut self, range:&Range) -> Result<(), &'static str> {
+        match unsafe { uhd_meta_range_push_back(self.handle, range) } {
+            0 => Ok(()),
+            _ => Err("Unable to push range onto meta_range")
+        }
+    }
+
+    pub fn get_at(&self, idx:usize) -> Result<Range, &'static str> {
+        let mut ans = Range::default();
+        match unsafe { uhd_meta_range_at(self.handle, idx, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to index into meta_range")
+        }
+    }
+
+    pub fn get_rust_vec(&self) -> Result<Vec<Range>, &'static str> {
+        let mut ans:Vec<Range> = vec![];
+        for idx in 0..(self.len()?) {
+            ans.push(self.get_at(idx)?);
+        }
+        Ok(ans)
+    }
+
+    pub fn to_pp_string(&self) -> Result<String, &'static str> {
+        let mut buff:Vec<u8> = vec![0u8; 1024];
+        match unsafe { uhd_meta_range_to_pp_string(self.handle, buff.as_mut_ptr(), buff.len()) } {
+            0 => Ok(unsafe { collect_cstr(buff.as_ptr()) }),
+            _ => Err("Unable to get meta_range as a string")
+        }
+    }
+
+}
+
+impl std::ops::Drop for MetaRange {
+
+    fn drop(&mut self) {
+        unsafe { uhd_meta_range_free(&mut self.handle); }
+    }
+
+}
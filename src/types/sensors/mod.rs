@@ -1,8 +1,11 @@
 
 use libc::c_char;
 
+use std::convert::TryFrom;
 use std::ffi::CString;
 
+use serde::{Deserialize, Serialize};
+
 use crate::UhdError;
 
 // From uhd/types/sensors.h
@@ -12,6 +15,18 @@ mod tests;
 
 pub type SensorValueHandle = usize;
 
+// String sensors can carry whole NMEA sentences, so this is sized well past anything UHD reports
+const STRING_BUFF_LEN:usize = 4096;
+
+// UHD copies with strncpy, so a string that fills the buffer may have been cut short without any error
+fn string_from_buff(buff:&[u8]) -> Result<String, &'static str> {
+    let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).copied().collect();
+    if nonzero.len() + 1 >= buff.len() {
+        return Err("String returned from UHD filled the buffer and may be truncated");
+    }
+    String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DataType {
@@ -21,6 +36,66 @@ pub enum DataType {
     String  = 115
 }
 
+// Sensors that show up across most USRP models.  Anything else can still be looked up by passing its name as a
+// string to the `get_*_sensor` calls.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Sensor {
+    LoLocked,
+    Rssi,
+    Temp,
+    RefLocked,
+    GpsLocked,
+    GpsTime,
+}
+
+impl Sensor {
+
+    pub const ALL:[Sensor; 6] = [Sensor::LoLocked, Sensor::Rssi, Sensor::Temp, Sensor::RefLocked, Sensor::GpsLocked, Sensor::GpsTime];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sensor::LoLocked  => "lo_locked",
+            Sensor::Rssi      => "rssi",
+            Sensor::Temp      => "temp",
+            Sensor::RefLocked => "ref_locked",
+            Sensor::GpsLocked => "gps_locked",
+            Sensor::GpsTime   => "gps_time",
+        }
+    }
+
+    pub fn from_name(name:&str) -> Option<Self> {
+        Self::ALL.iter().find(|s| s.name() == name).copied()
+    }
+
+    // The data type UHD reports for this sensor
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Sensor::LoLocked | Sensor::RefLocked | Sensor::GpsLocked => DataType::Boolean,
+            Sensor::Rssi | Sensor::Temp => DataType::RealNum,
+            Sensor::GpsTime => DataType::Integer,
+        }
+    }
+
+}
+
+impl AsRef<str> for Sensor {
+    fn as_ref(&self) -> &str { self.name() }
+}
+
+impl std::str::FromStr for Sensor {
+    type Err = &'static str;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or("Unrecognized sensor name")
+    }
+}
+
+impl std::fmt::Display for Sensor {
+    fn fmt(&self, f:&mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 #[link(name = "uhd")]
 extern {
 
@@ -45,6 +120,71 @@ extern {
 
 }
 
+// A sensor value read out of UHD in one go, tagged by the data type UHD reported for it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SensorReading {
+    Bool{ name:String, value:bool, unit:String },
+    Int{ name:String, value:i32, unit:String },
+    Real{ name:String, value:f64, unit:String },
+    String{ name:String, value:String, unit:String },
+}
+
+impl SensorReading {
+
+    pub fn name(&self) -> &str {
+        match self {
+            SensorReading::Bool{ name, .. } | SensorReading::Int{ name, .. } |
+            SensorReading::Real{ name, .. } | SensorReading::String{ name, .. } => name,
+        }
+    }
+
+    pub fn unit(&self) -> &str {
+        match self {
+            SensorReading::Bool{ unit, .. } | SensorReading::Int{ unit, .. } |
+            SensorReading::Real{ unit, .. } | SensorReading::String{ unit, .. } => unit,
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            SensorReading::Bool{ .. }   => DataType::Boolean,
+            SensorReading::Int{ .. }    => DataType::Integer,
+            SensorReading::Real{ .. }   => DataType::RealNum,
+            SensorReading::String{ .. } => DataType::String,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SensorReading::Int{ value, .. } => Some(*value as f64),
+            SensorReading::Real{ value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Builds the equivalent UHD sensor value.  For booleans UHD keeps the unit string for whichever state the
+    // value is in, so passing the same unit for both states reproduces it exactly.  Real numbers are formatted
+    // with enough digits that `to_realnum` gives back the same f64.
+    pub fn to_sensor_value(&self) -> Result<SensorValue, &'static str> {
+        match self {
+            SensorReading::Bool{ name, value, unit }   => SensorValue::from_bool(name, *value, unit, unit),
+            SensorReading::Int{ name, value, unit }    => SensorValue::from_int(name, *value, unit, "%d"),
+            SensorReading::Real{ name, value, unit }   => SensorValue::from_realnum(name, *value, unit, "%.17g"),
+            SensorReading::String{ name, value, unit } => SensorValue::from_string(name, value, unit),
+        }
+    }
+
+}
+
+impl TryFrom<&SensorValue> for SensorReading {
+    type Error = &'static str;
+
+    fn try_from(value:&SensorValue) -> Result<Self, Self::Error> {
+        value.read()
+    }
+}
+
 pub struct SensorValue {
     handle: SensorValueHandle
 }
@@ -108,56 +248,41 @@ impl SensorValue {
     }
 
     pub fn get_name(&self) -> Result<String, &'static str> {
-        let mut buff:Vec<u8> = vec![0u8; 64];
-        match unsafe { uhd_sensor_value_name(self.handle, buff.as_mut_ptr(), 64) } {
-            0 => {
-                let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).map(|x| *x).collect();
-                Ok(String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")?)
-            },
+        let mut buff:Vec<u8> = vec![0u8; STRING_BUFF_LEN];
+        match unsafe { uhd_sensor_value_name(self.handle, buff.as_mut_ptr(), STRING_BUFF_LEN) } {
+            0 => string_from_buff(&buff),
             _ => Err("Nonzero return value from C API call in SensorValue::get_name")
         }
     }
 
     pub fn get_value(&self) -> Result<String, &'static str> {
-        let mut buff:Vec<u8> = vec![0u8; 64];
-        match unsafe { uhd_sensor_value_value(self.handle, buff.as_mut_ptr(), 64) } {
-            0 => {
-                let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).map(|x| *x).collect();
-                Ok(String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")?)
-            },
+        let mut buff:Vec<u8> = vec![0u8; STRING_BUFF_LEN];
+        match unsafe { uhd_sensor_value_value(self.handle, buff.as_mut_ptr(), STRING_BUFF_LEN) } {
+            0 => string_from_buff(&buff),
             _ => Err("Nonzero return value from C API call in SensorValue::get_value")
         }
     }
 
     pub fn get_unit(&self) -> Result<String, &'static str> {
-        let mut buff:Vec<u8> = vec![0u8; 64];
-        match unsafe { uhd_sensor_value_unit(self.handle, buff.as_mut_ptr(), 64) } {
-            0 => {
-                let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).map(|x| *x).collect();
-                Ok(String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")?)
-            },
+        let mut buff:Vec<u8> = vec![0u8; STRING_BUFF_LEN];
+        match unsafe { uhd_sensor_value_unit(self.handle, buff.as_mut_ptr(), STRING_BUFF_LEN) } {
+            0 => string_from_buff(&buff),
             _ => Err("Nonzero return value from C API call in SensorValue::get_unit")
         }
     }
 
     pub fn to_pp_string(&self) -> Result<String, &'static str> {
-        let mut buff:Vec<u8> = vec![0u8; 64];
-        match unsafe { uhd_sensor_value_to_pp_string(self.handle, buff.as_mut_ptr(), 64) } {
-            0 => {
-                let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).map(|x| *x).collect();
-                Ok(String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")?)
-            },
+        let mut buff:Vec<u8> = vec![0u8; STRING_BUFF_LEN];
+        match unsafe { uhd_sensor_value_to_pp_string(self.handle, buff.as_mut_ptr(), STRING_BUFF_LEN) } {
+            0 => string_from_buff(&buff),
             _ => Err("Nonzero return value from C API call in SensorValue::to_pp_string")
         }
     }
 
     pub fn last_error(&self) -> Result<String, &'static str> {
-        let mut buff:Vec<u8> = vec![0u8; 64];
-        match unsafe { uhd_sensor_value_last_error(self.handle, buff.as_mut_ptr(), 64) } {
-            0 => {
-                let nonzero:Vec<u8> = buff.iter().take_while(|x| **x != 0).map(|x| *x).collect();
-                Ok(String::from_utf8(nonzero).map_err(|_| "Unable to convert string returned from UHD as UTF-8")?)
-            },
+        let mut buff:Vec<u8> = vec![0u8; STRING_BUFF_LEN];
+        match unsafe { uhd_sensor_value_last_error(self.handle, buff.as_mut_ptr(), STRING_BUFF_LEN) } {
+            0 => string_from_buff(&buff),
             _ => Err("Nonzero return value from C API call in SensorValue::last_error")
         }
     }
@@ -170,6 +295,17 @@ impl SensorValue {
         }
     }
 
+    pub fn read(&self) -> Result<SensorReading, &'static str> {
+        let name = self.get_name()?;
+        let unit = self.get_unit()?;
+        Ok(match self.get_data_type()? {
+            DataType::Boolean => SensorReading::Bool{ name, value: self.to_bool()?, unit },
+            DataType::Integer => SensorReading::Int{ name, value: self.to_int()?, unit },
+            DataType::RealNum => SensorReading::Real{ name, value: self.to_realnum()?, unit },
+            DataType::String  => SensorReading::String{ name, value: self.get_value()?, unit },
+        })
+    }
+
     pub fn to_bool(&self) -> Result<bool, &'static str> {
         let mut ans = false;
         match unsafe { uhd_sensor_value_to_bool(self.handle, &mut ans) } {
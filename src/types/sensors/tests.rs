@@ -1,5 +1,5 @@
 
-use crate::types::sensors::{SensorValue, DataType};
+use crate::types::sensors::{string_from_buff, Sensor, SensorReading, SensorValue, DataType};
 
 #[test]
 fn create_bool_sensor_value() {
@@ -40,3 +40,44 @@ fn create_realnum_sensor_value() {
     assert_eq!("", x.get_unit().unwrap());
 }
 
+#[test]
+fn sensor_names_round_trip() {
+    for sensor in Sensor::ALL.iter() {
+        assert_eq!(Some(*sensor), Sensor::from_name(sensor.name()));
+        assert_eq!(*sensor, sensor.to_string().parse::<Sensor>().unwrap());
+    }
+    assert_eq!("ref_locked", Sensor::RefLocked.as_ref());
+    assert_eq!(None, Sensor::from_name("not_a_sensor"));
+}
+
+#[test]
+fn sensor_reading_round_trip() {
+    let readings = vec![
+        SensorReading::Bool{ name: "ref_locked".to_owned(), value: true, unit: "locked".to_owned() },
+        SensorReading::Int{ name: "gps_time".to_owned(), value: 1_234_567, unit: "seconds".to_owned() },
+        SensorReading::Real{ name: "temp".to_owned(), value: 41.123456789012345, unit: "C".to_owned() },
+        SensorReading::String{ name: "gps_gpgga".to_owned(), value: "$GPGGA,".to_owned(), unit: "".to_owned() },
+    ];
+
+    for reading in readings {
+        let value = reading.to_sensor_value().unwrap();
+        assert_eq!(reading.data_type(), value.get_data_type().unwrap());
+        assert_eq!(reading, value.read().unwrap());
+    }
+}
+
+#[test]
+fn sensor_reading_serde() {
+    let reading = SensorReading::Real{ name: "rssi".to_owned(), value: -71.5, unit: "dBm".to_owned() };
+    let json = serde_json::to_string(&reading).unwrap();
+    assert_eq!(r#"{"type":"Real","name":"rssi","value":-71.5,"unit":"dBm"}"#, json);
+    assert_eq!(reading, serde_json::from_str::<SensorReading>(&json).unwrap());
+    assert_eq!(Some(-71.5), reading.as_f64());
+}
+
+#[test]
+fn full_string_buffer_is_an_error() {
+    assert_eq!(Ok("GPGGA".to_owned()), string_from_buff(b"GPGGA\0\0\0"));
+    assert!(string_from_buff(b"GPGGA,1\0").is_err());
+    assert!(string_from_buff(b"GPGGA,12").is_err());
+}
@@ -0,0 +1,100 @@
+use std::ffi::CString;
+
+use crate::types::device_args::DeviceArgs;
+use crate::usrp::StreamArgs;
+
+#[cfg(test)]
+mod tests;
+
+// Format of samples on the wire between the host and the device
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OtwFormat { Sc16, Sc12, Sc8 }
+
+impl OtwFormat {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OtwFormat::Sc16 => "sc16",
+            OtwFormat::Sc12 => "sc12",
+            OtwFormat::Sc8  => "sc8",
+        }
+    }
+
+}
+
+// Owned counterpart of `StreamArgs`; the C struct is only built for the duration of the call that needs it.  The
+// streamers only read and write sc16 buffers, so that's always the host format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamArgsBuilder {
+    pub otw_format:OtwFormat,
+    pub spp:Option<usize>,          // Samples per packet
+    pub channels:Vec<usize>,
+    pub args:DeviceArgs,            // Anything not covered by the typed options above
+}
+
+impl StreamArgsBuilder {
+
+    pub fn new() -> Self {
+        Self {
+            otw_format: OtwFormat::Sc16, spp: None,
+            channels: vec![0], args: DeviceArgs::new()
+        }
+    }
+
+    pub fn otw_format(mut self, format:OtwFormat) -> Self { self.otw_format = format; self }
+    pub fn spp(mut self, spp:usize) -> Self { self.spp = Some(spp); self }
+    pub fn channels(mut self, channels:&[usize]) -> Self { self.channels = channels.to_vec(); self }
+
+    pub fn arg<K: AsRef<str>, V: ToString>(mut self, key:K, value:V) -> Self {
+        self.args.insert(key, value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.channels.is_empty() {
+            return Err("Stream needs at least one channel");
+        }
+        if self.channels.iter().enumerate().any(|(i, c)| self.channels[..i].contains(c)) {
+            return Err("Stream channel list contains a duplicate");
+        }
+        if self.spp == Some(0) {
+            return Err("Samples per packet must be greater than zero");
+        }
+        Ok(())
+    }
+
+    // Typed options first, then any extra args; a typed option wins if the same key shows up in both
+    pub fn args_string(&self) -> String {
+        let mut args = DeviceArgs::new();
+        if let Some(spp) = self.spp { args.insert("spp", spp); }
+        for (key, value) in self.args.iter() {
+            if !args.contains_key(key) {
+                args.insert(key, value);
+            }
+        }
+        args.to_string()
+    }
+
+    pub(crate) fn with_c_args<T, F: FnOnce(&StreamArgs) -> Result<T, &'static str>>(&self, f:F) -> Result<T, &'static str> {
+        self.validate()?;
+
+        let cpu_format = CString::new("sc16").map_err(|_| "Unable to build CString from CPU format")?;
+        let otw_format = CString::new(self.otw_format.name()).map_err(|_| "Unable to build CString from wire format")?;
+        let args = CString::new(self.args_string()).map_err(|_| "Stream args can't contain a nul byte")?;
+
+        let stream_args = StreamArgs {
+            cpu_format: cpu_format.as_ptr(),
+            otw_format: otw_format.as_ptr(),
+            args: args.as_ptr(),
+            channel_list: self.channels.as_ptr(),
+            n_channels: self.channels.len() as isize
+        };
+
+        f(&stream_args)
+    }
+
+}
+
+impl Default for StreamArgsBuilder {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,47 @@
+use std::ffi::CStr;
+
+use crate::types::stream_args::{OtwFormat, StreamArgsBuilder};
+
+#[test]
+fn defaults() {
+    let args = StreamArgsBuilder::new();
+    assert!(args.validate().is_ok());
+    assert_eq!(vec![0], args.channels);
+    assert_eq!("", args.args_string());
+}
+
+#[test]
+fn invalid_combinations() {
+    assert!(StreamArgsBuilder::new().channels(&[]).validate().is_err());
+    assert!(StreamArgsBuilder::new().channels(&[0, 1, 0]).validate().is_err());
+    assert!(StreamArgsBuilder::new().spp(0).validate().is_err());
+
+    assert!(StreamArgsBuilder::new().otw_format(OtwFormat::Sc8).spp(100).validate().is_ok());
+}
+
+#[test]
+fn c_args() {
+    let args = StreamArgsBuilder::new()
+        .otw_format(OtwFormat::Sc12)
+        .channels(&[2, 3])
+        .spp(200)
+        .arg("spp", 100)
+        .arg("underflow_policy", "next_burst");
+    assert_eq!("spp=200,underflow_policy=next_burst", args.args_string());
+
+    let (cpu, otw, n_channels, second) = args.with_c_args(|c| unsafe {
+        Ok((
+            CStr::from_ptr(c.cpu_format).to_str().unwrap().to_owned(),
+            CStr::from_ptr(c.otw_format).to_str().unwrap().to_owned(),
+            c.n_channels,
+            *c.channel_list.add(1)
+        ))
+    }).unwrap();
+
+    assert_eq!("sc16", cpu);
+    assert_eq!("sc12", otw);
+    assert_eq!(2, n_channels);
+    assert_eq!(3, second);
+
+    assert!(StreamArgsBuilder::new().channels(&[]).with_c_args(|_| Ok(())).is_err());
+}
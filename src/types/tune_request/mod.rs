@@ -0,0 +1,103 @@
+use std::ffi::CString;
+
+use crate::types::{TuneRequest, TuneRequestPolicy};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FreqPolicy {
+    Auto,           // Let UHD pick the frequency
+    Manual(f64),    // Use exactly this frequency in Hz
+    None,           // Leave this stage where it is
+}
+
+impl FreqPolicy {
+
+    fn to_c(self) -> (TuneRequestPolicy, f64) {
+        match self {
+            FreqPolicy::Auto => (TuneRequestPolicy::Auto, 0.0),
+            FreqPolicy::Manual(freq) => (TuneRequestPolicy::Manual, freq),
+            FreqPolicy::None => (TuneRequestPolicy::None, 0.0),
+        }
+    }
+
+}
+
+// Synthesizer mode on frontends that support both; integer-N has lower spurs but coarser steps
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ModeN { Integer, Fractional }
+
+impl ModeN {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModeN::Integer => "integer",
+            ModeN::Fractional => "fractional",
+        }
+    }
+
+}
+
+// Owned counterpart of `TuneRequest` that keeps the args string alive for as long as the C struct needs it
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuneRequestBuilder {
+    pub target_freq:f64,
+    pub rf_policy:FreqPolicy,
+    pub dsp_policy:FreqPolicy,
+    pub mode_n:Option<ModeN>,
+    pub args:Vec<(String, String)>,
+}
+
+impl TuneRequestBuilder {
+
+    pub fn new(target_freq:f64) -> Self {
+        Self{ target_freq, rf_policy: FreqPolicy::Auto, dsp_policy: FreqPolicy::Auto, mode_n: None, args: vec![] }
+    }
+
+    // Same as UHD's tune_request_t(target, lo_off): the LO sits `offset` Hz away from the target and the DSP makes
+    // up the difference, which moves the LO leakage out of the band of interest
+    pub fn lo_offset(mut self, offset:f64) -> Self {
+        self.rf_policy = FreqPolicy::Manual(self.target_freq + offset);
+        self.dsp_policy = FreqPolicy::Auto;
+        self
+    }
+
+    pub fn rf_freq(self, freq:f64) -> Self { self.rf_policy(FreqPolicy::Manual(freq)) }
+    pub fn dsp_freq(self, freq:f64) -> Self { self.dsp_policy(FreqPolicy::Manual(freq)) }
+    pub fn rf_policy(mut self, policy:FreqPolicy) -> Self { self.rf_policy = policy; self }
+    pub fn dsp_policy(mut self, policy:FreqPolicy) -> Self { self.dsp_policy = policy; self }
+    pub fn mode_n(mut self, mode:ModeN) -> Self { self.mode_n = Some(mode); self }
+
+    pub fn arg<K: AsRef<str>, V: AsRef<str>>(mut self, key:K, value:V) -> Self {
+        self.args.push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    // Key-value pairs delimited by commas, the way UHD expects them
+    pub fn args_string(&self) -> String {
+        let mode_n = self.mode_n.map(|m| ("mode_n", m.name()));
+        mode_n.into_iter()
+            .chain(self.args.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    // The C struct borrows the args string, so it's only handed out for the duration of the closure
+    pub(crate) fn with_c_request<T, F: FnOnce(&TuneRequest) -> Result<T, &'static str>>(&self, f:F) -> Result<T, &'static str> {
+        let args = CString::new(self.args_string()).map_err(|_| "Tune request args can't contain a nul byte")?;
+        let (rf_freq_policy, rf_freq) = self.rf_policy.to_c();
+        let (dsp_freq_policy, dsp_freq) = self.dsp_policy.to_c();
+
+        let tune_request = TuneRequest {
+            target_freq: self.target_freq,
+            rf_freq_policy, rf_freq,
+            dsp_freq_policy, dsp_freq,
+            args: args.as_ptr()
+        };
+
+        f(&tune_request)
+    }
+
+}
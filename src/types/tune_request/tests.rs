@@ -0,0 +1,42 @@
+use std::ffi::CStr;
+
+use crate::types::TuneRequestPolicy;
+use crate::types::tune_request::{FreqPolicy, ModeN, TuneRequestBuilder};
+
+#[test]
+fn lo_offset_policies() {
+    let request = TuneRequestBuilder::new(915.0e6).lo_offset(5.0e6);
+    assert_eq!(FreqPolicy::Manual(920.0e6), request.rf_policy);
+    assert_eq!(FreqPolicy::Auto, request.dsp_policy);
+}
+
+#[test]
+fn args_string() {
+    assert_eq!("", TuneRequestBuilder::new(1.0e9).args_string());
+
+    let request = TuneRequestBuilder::new(1.0e9)
+        .arg("int_n_step", "100e3")
+        .mode_n(ModeN::Integer);
+    assert_eq!("mode_n=integer,int_n_step=100e3", request.args_string());
+}
+
+#[test]
+fn c_request() {
+    let request = TuneRequestBuilder::new(2.4e9)
+        .rf_freq(2.41e9)
+        .dsp_policy(FreqPolicy::None)
+        .mode_n(ModeN::Fractional);
+
+    let (rf, dsp, args) = request.with_c_request(|r| {
+        assert_eq!(2.4e9, r.target_freq);
+        assert_eq!(2.41e9, r.rf_freq);
+        let args = unsafe { CStr::from_ptr(r.args) }.to_str().unwrap().to_owned();
+        Ok((r.rf_freq_policy, r.dsp_freq_policy, args))
+    }).unwrap();
+
+    assert_eq!(TuneRequestPolicy::Manual, rf);
+    assert_eq!(TuneRequestPolicy::None, dsp);
+    assert_eq!("mode_n=fractional", args);
+
+    assert!(TuneRequestBuilder::new(1.0e9).arg("bad", "a\0b").with_c_request(|_| Ok(())).is_err());
+}
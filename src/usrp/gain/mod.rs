@@ -0,0 +1,27 @@
+use crate::types::ranges::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Splits a requested total gain across a list of named gain elements.  Every element starts at the bottom
+// of its range and the elements are then filled in the order given, so the first element saturates before
+// the second one is touched, and so on.  Values snap down onto each element's step grid and anything that
+// can't be placed is left over rather than overshooting the request.
+pub fn distribute(total:f64, elements:&[(String, Range)]) -> Vec<(String, f64)> {
+
+    let min_total:f64 = elements.iter().map(|(_, r)| r.start).sum();
+    let mut remaining:f64 = total - min_total;
+
+    elements.iter().map(|(name, range)| {
+        let headroom:f64 = range.stop - range.start;
+        let wanted:f64 = remaining.max(0.0).min(headroom);
+        let value:f64 = range.clip(range.start + wanted, true);
+        remaining -= value - range.start;
+        (name.clone(), value)
+    }).collect()
+
+}
+
+pub fn total(distribution:&[(String, f64)]) -> f64 {
+    distribution.iter().map(|(_, g)| g).sum()
+}
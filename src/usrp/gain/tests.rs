@@ -0,0 +1,42 @@
+use crate::types::ranges::Range;
+use crate::usrp::gain::{distribute, total};
+
+fn twinrx_like() -> Vec<(String, Range)> {
+    vec![
+        ("LNA".to_owned(),  Range::new(0.0, 30.0, 1.0)),
+        ("PGA".to_owned(),  Range::new(0.0, 31.5, 0.5)),
+        ("ATTN".to_owned(), Range::new(-10.0, 0.0, 2.0)),
+    ]
+}
+
+#[test]
+fn fills_elements_in_order() {
+    let d = distribute(40.0, &twinrx_like());
+    // Minimum total is -10 dB, so 50 dB has to be placed on top of the minimums
+    assert_eq!(d[0], ("LNA".to_owned(), 30.0));
+    assert_eq!(d[1], ("PGA".to_owned(), 20.0));
+    assert_eq!(d[2], ("ATTN".to_owned(), -10.0));
+    assert_eq!(40.0, total(&d));
+}
+
+#[test]
+fn clamps_to_range_limits() {
+    let low = distribute(-100.0, &twinrx_like());
+    assert_eq!(-10.0, total(&low));
+
+    let high = distribute(1000.0, &twinrx_like());
+    assert_eq!(61.5, total(&high));
+}
+
+#[test]
+fn respects_step_size() {
+    let d = distribute(33.25, &twinrx_like());
+    assert_eq!(d[0].1, 30.0);
+    assert_eq!(d[1].1, 13.0);
+    assert_eq!(d[2].1, -10.0);
+}
+
+#[test]
+fn empty_element_list() {
+    assert!(distribute(10.0, &[]).is_empty());
+}
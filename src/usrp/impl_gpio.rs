@@ -0,0 +1,49 @@
+use std::ffi::CString;
+
+use libc::{size_t, c_char};
+
+use crate::usrp::USRP;
+use crate::types::string_vector::StringVector;
+use crate::UhdError;
+
+#[link(name = "uhd")]
+extern {
+
+    fn uhd_usrp_get_gpio_banks(h:usize, mboard:size_t, gpio_banks_out:&mut usize) -> UhdError;
+    fn uhd_usrp_set_gpio_attr(h:usize, bank:*const c_char, attr:*const c_char, value:u32, mask:u32, mboard:size_t) -> UhdError;
+    fn uhd_usrp_get_gpio_attr(h:usize, bank:*const c_char, attr:*const c_char, mboard:size_t, attr_out:&mut u32) -> UhdError;
+
+}
+
+impl USRP {
+
+    pub fn get_gpio_banks(&self, mboard:usize) -> Result<Vec<String>, &'static str> {
+        let mut string_vec = StringVector::new()?;
+        match unsafe { uhd_usrp_get_gpio_banks(self.handle, mboard, &mut string_vec.handle) } {
+            0 => Ok(string_vec.get_rust_vec()?),
+            _ => Err("Unable to get GPIO banks")
+        }
+    }
+
+    // Only the bits set in `mask` are changed.  Attributes include CTRL, DDR, OUT and ATR_* as described in the
+    // UHD GPIO documentation.
+    pub fn set_gpio_attr(&mut self, bank:&str, attr:&str, value:u32, mask:u32, mboard:usize) -> Result<(), &'static str> {
+        let bank_c = CString::new(bank).map_err(|_| "Unable to represent `bank` as a CString")?;
+        let attr_c = CString::new(attr).map_err(|_| "Unable to represent `attr` as a CString")?;
+        match unsafe { uhd_usrp_set_gpio_attr(self.handle, bank_c.as_ptr(), attr_c.as_ptr(), value, mask, mboard) } {
+            0 => Ok(()),
+            _ => Err("Unable to set GPIO attribute")
+        }
+    }
+
+    pub fn get_gpio_attr(&self, bank:&str, attr:&str, mboard:usize) -> Result<u32, &'static str> {
+        let bank_c = CString::new(bank).map_err(|_| "Unable to represent `bank` as a CString")?;
+        let attr_c = CString::new(attr).map_err(|_| "Unable to represent `attr` as a CString")?;
+        let mut ans:u32 = 0;
+        match unsafe { uhd_usrp_get_gpio_attr(self.handle, bank_c.as_ptr(), attr_c.as_ptr(), mboard, &mut ans) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get GPIO attribute")
+        }
+    }
+
+}
@@ -2,25 +2,29 @@
 use std::ffi::CString;
 
 use libc::{c_char, size_t};
-use crate::c_interop::{collect_cstr, populate_cstr};
+use crate::c_interop::{collect_cstr, cstr_from_buff, populate_cstr};
 
 use crate::check_err;
 use crate::rx_streamer::RxStreamer;
-use crate::types::{TuneRequest, TuneResult, TuneRequestPolicy};
+use crate::types::{TuneRequest, TuneResult};
+use crate::types::device_args::DeviceArgs;
+use crate::types::ranges::{MetaRange, Range};
+use crate::types::stream_args::StreamArgsBuilder;
 use crate::types::string_vector::StringVector;
+use crate::types::tune_request::TuneRequestBuilder;
 use crate::types::usrp_info::Info;
-use crate::usrp::{StreamArgs, StreamCmd};
+use crate::usrp::{gain, StreamArgs, StreamCmd};
 use crate::usrp::subdev_spec::SubdevSpec;
 
 #[link(name = "uhd")]
 extern {
 
-	// uhd_error uhd_usrp_set_rx_subdev_spec(uhd_usrp_handle h, uhd_subdev_spec_handle subdev_spec, size_t mboard)
+	fn uhd_usrp_set_rx_subdev_spec(h: usize, subdev_spec: usize, mboard: usize) -> isize;
 	fn uhd_usrp_get_rx_subdev_spec(h: usize, mboard: usize, subdev_spec_out: usize) -> isize;
 	fn uhd_usrp_get_rx_subdev_name(h: usize, chan: size_t, rx_subdev_name_out: *mut u8, strbuffer_len: size_t) -> isize;
 
-	// uhd_error uhd_usrp_get_rx_freq_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle freq_range_out)
-	// uhd_error uhd_usrp_get_fe_rx_freq_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle freq_range_out)
+	fn uhd_usrp_get_rx_freq_range(h:usize, chan:size_t, freq_range_out:usize) -> isize;
+	fn uhd_usrp_get_fe_rx_freq_range(h:usize, chan:size_t, freq_range_out:usize) -> isize;
 
 	fn uhd_usrp_get_rx_lo_names(h: usize, chan: size_t, rx_lo_names_out: *mut usize) -> isize;
 	fn uhd_usrp_set_rx_lo_source(h: usize, src: *const u8, name: *const u8, chan: size_t) -> isize;
@@ -29,21 +33,19 @@ extern {
 	fn uhd_usrp_set_rx_lo_export_enabled(h: usize, enabled: bool, name: *const u8, chan: size_t) -> isize;
 	fn uhd_usrp_get_rx_lo_export_enabled(h: usize, name: *const u8, chan: size_t, result_out: *mut bool) -> isize;
 
-	// uhd_error uhd_usrp_set_rx_lo_freq(uhd_usrp_handle h, double freq, const char* name, size_t chan, double* coerced_freq_out)
-	// uhd_error uhd_usrp_get_rx_lo_freq(uhd_usrp_handle h, const char* name, size_t chan, double* rx_lo_freq_out)
-	// uhd_error uhd_usrp_set_normalized_rx_gain(uhd_usrp_handle h, double gain, size_t chan)
-	// uhd_error uhd_usrp_set_rx_agc(uhd_usrp_handle h, bool enable, size_t chan)
-	// uhd_error uhd_usrp_get_normalized_rx_gain(uhd_usrp_handle h, size_t chan, double *gain_out)
-	// uhd_error uhd_usrp_get_rx_gain_range(uhd_usrp_handle h, const char* name, size_t chan, uhd_meta_range_handle gain_range_out)
-	// uhd_error uhd_usrp_get_rx_gain_names(uhd_usrp_handle h, size_t chan, uhd_string_vector_handle *gain_names_out)
-	// uhd_error uhd_usrp_set_rx_antenna(uhd_usrp_handle h, const char* ant, size_t chan)
-	// uhd_error uhd_usrp_get_rx_antenna(uhd_usrp_handle h, size_t chan, char* ant_out, size_t strbuffer_len)
-
-	// uhd_error uhd_usrp_get_rx_sensor_names(uhd_usrp_handle h, size_t chan, uhd_string_vector_handle *sensor_names_out)
-	// uhd_error uhd_usrp_get_rx_bandwidth_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle bandwidth_range_out)
-	// uhd_error uhd_usrp_get_rx_sensor(uhd_usrp_handle h, const char* name, size_t chan, uhd_sensor_value_handle *sensor_value_out)
-	// uhd_error uhd_usrp_set_rx_dc_offset_enabled(uhd_usrp_handle h, bool enb, size_t chan)
-	// uhd_error uhd_usrp_set_rx_iq_balance_enabled(uhd_usrp_handle h, bool enb, size_t chan)
+	fn uhd_usrp_set_rx_lo_freq(h: usize, freq: f64, name: *const u8, chan: size_t, coerced_freq_out: *mut f64) -> isize;
+	fn uhd_usrp_get_rx_lo_freq(h: usize, name: *const u8, chan: size_t, rx_lo_freq_out: *mut f64) -> isize;
+	fn uhd_usrp_set_normalized_rx_gain(h:usize, gain:f64, chan:size_t) -> isize;
+	fn uhd_usrp_set_rx_agc(h:usize, enable:bool, chan:size_t) -> isize;
+	fn uhd_usrp_get_normalized_rx_gain(h:usize, chan:size_t, gain_out:&mut f64) -> isize;
+	fn uhd_usrp_get_rx_gain_range(h:usize, name:*const c_char, chan:size_t, gain_range_out:usize) -> isize;
+	fn uhd_usrp_get_rx_gain_names(h:usize, chan:size_t, gain_names_out:&mut usize) -> isize;
+	fn uhd_usrp_set_rx_antenna(h:usize, ant:*const c_char, chan:size_t) -> isize;
+	fn uhd_usrp_get_rx_antenna(h:usize, chan:size_t, ant_out:*mut u8, strbuffer_len:size_t) -> isize;
+
+	fn uhd_usrp_get_rx_bandwidth_range(h:usize, chan:size_t, bandwidth_range_out:usize) -> isize;
+	fn uhd_usrp_set_rx_dc_offset_enabled(h:usize, enb:bool, chan:size_t) -> isize;
+	fn uhd_usrp_set_rx_iq_balance_enabled(h:usize, enb:bool, chan:size_t) -> isize;
 
 	fn uhd_usrp_get_rx_info(h:usize, chan:size_t, info_out:&mut Info) -> isize;
 	fn uhd_usrp_get_rx_num_channels(h:usize, num_channels_out:&mut size_t) -> isize;
@@ -52,6 +54,7 @@ extern {
 
 	fn uhd_usrp_set_rx_rate(h:usize, rate:f64, chan:size_t) -> isize;
 	fn uhd_usrp_get_rx_rate(h:usize, chan:size_t, rate_out:&mut f64) -> isize;
+	fn uhd_usrp_get_rx_rates(h:usize, chan:size_t, rates_out:usize) -> isize;
 	fn uhd_usrp_set_rx_gain(h:usize, gain:f64, chan:size_t, gain_name:*const c_char) -> isize;
 	fn uhd_usrp_get_rx_gain(h:usize, chan:size_t, gain_name:*const c_char, gain_out:&mut f64) -> isize;
 	fn uhd_usrp_set_rx_freq(h:usize, tune_request:&TuneRequest, chan:size_t, tune_result:&mut TuneResult) -> isize;
@@ -129,6 +132,39 @@ impl super::USRP {
 		}
 	}
 
+	// Returns the frequency the LO was actually coerced to
+	pub fn set_rx_lo_freq(&mut self, freq: f64, name: &str, chan: usize) -> Result<f64, &'static str> {
+		let mut name_buff: Vec<u8> = vec![0; 64];
+		let mut coerced: f64 = 0.0;
+		unsafe {
+			populate_cstr(name_buff.as_mut_ptr(), name_buff.len(), name);
+			let result = uhd_usrp_set_rx_lo_freq(self.handle, freq, name_buff.as_ptr(), chan, &mut coerced);
+			match result {
+				0 => Ok(coerced),
+				_ => {
+					eprintln!("{:?}", self.last_error());
+					Err("Unable to set LO freq")
+				}
+			}
+		}
+	}
+
+	pub fn get_rx_lo_freq(&self, name: &str, chan: usize) -> Result<f64, &'static str> {
+		let mut name_buff: Vec<u8> = vec![0; 64];
+		let mut freq: f64 = 0.0;
+		unsafe {
+			populate_cstr(name_buff.as_mut_ptr(), name_buff.len(), name);
+			let result = uhd_usrp_get_rx_lo_freq(self.handle, name_buff.as_ptr(), chan, &mut freq);
+			match result {
+				0 => Ok(freq),
+				_ => {
+					eprintln!("{:?}", self.last_error());
+					Err("Unable to get LO freq")
+				}
+			}
+		}
+	}
+
 	pub fn get_rx_lo_names(&self, chan: usize) -> Result<StringVector, &'static str> {
 		let mut sv = StringVector::new()?;
 		check_err((), unsafe {
@@ -148,6 +184,10 @@ impl super::USRP {
 	}
 
 	pub fn get_subdev_spec(&self, mboard: usize) -> Result<SubdevSpec, &'static str> {
+		self.get_rx_subdev_spec(mboard)
+	}
+
+	pub fn get_rx_subdev_spec(&self, mboard: usize) -> Result<SubdevSpec, &'static str> {
 		let spec = SubdevSpec::new("A0")?;
 		unsafe {
 			match uhd_usrp_get_rx_subdev_spec(self.handle, mboard, spec.handle) {
@@ -157,6 +197,36 @@ impl super::USRP {
 		}
 	}
 
+	// The device rejects frontends it doesn't have; reading the spec back catches any it silently remaps.
+	// With `ALL_MBOARDS` every mboard is read back.
+	pub fn set_rx_subdev_spec(&mut self, spec: &SubdevSpec, mboard: usize) -> Result<(), &'static str> {
+		spec.validate()?;
+		check_err((), unsafe { uhd_usrp_set_rx_subdev_spec(self.handle, spec.handle, mboard) })?;
+		for mboard in self.mboards(mboard)? {
+			if !spec.matches(&self.get_rx_subdev_spec(mboard)?)? {
+				return Err("Device did not accept the RX subdev spec as given");
+			}
+		}
+		Ok(())
+	}
+
+	// Sets the spec to check that the device takes it, then puts back what every affected mboard had before, so the
+	// channel mapping is unchanged afterwards.  An error from the attempt takes priority over one from restoring.
+	pub fn try_rx_subdev_spec(&mut self, spec: &SubdevSpec, mboard: usize) -> Result<(), &'static str> {
+		let previous = self.mboards(mboard)?
+			.map(|mboard| Ok((mboard, self.get_rx_subdev_spec(mboard)?)))
+			.collect::<Result<Vec<(usize, SubdevSpec)>, &'static str>>()?;
+		let ans = self.set_rx_subdev_spec(spec, mboard);
+
+		let mut restored = Ok(());
+		for (mboard, spec) in previous.iter() {
+			if unsafe { uhd_usrp_set_rx_subdev_spec(self.handle, spec.handle, *mboard) } != 0 {
+				restored = Err("Unable to restore RX subdev spec");
+			}
+		}
+		ans.and(restored)
+	}
+
 	pub fn get_rx_subdev_name(&self, chan: usize) -> Result<String, &'static str> {
 		let mut buff: Vec<u8> = vec![0; 128];
 		unsafe {
@@ -183,7 +253,31 @@ impl super::USRP {
 		check_err((), unsafe { uhd_usrp_set_rx_bandwidth(self.handle, bandwidth, chan) })
 	}
 
-	pub fn start_continuous_stream(&mut self, args:&str) -> Result<RxStreamer, &'static str> {
+	pub fn get_rx_bandwidth_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		check_err((), unsafe { uhd_usrp_get_rx_bandwidth_range(self.handle, chan, range.handle) })?;
+		Ok(range)
+	}
+
+	pub fn get_rx_rates(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		check_err((), unsafe { uhd_usrp_get_rx_rates(self.handle, chan, range.handle) })?;
+		Ok(range)
+	}
+
+	pub fn get_rx_freq_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		check_err((), unsafe { uhd_usrp_get_rx_freq_range(self.handle, chan, range.handle) })?;
+		Ok(range)
+	}
+
+	pub fn get_fe_rx_freq_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		check_err((), unsafe { uhd_usrp_get_fe_rx_freq_range(self.handle, chan, range.handle) })?;
+		Ok(range)
+	}
+
+	pub fn start_continuous_stream(&mut self, args:impl Into<DeviceArgs>) -> Result<RxStreamer, &'static str> {
 		
 		let mut rx_streamer = self.get_rx_stream(args, &[0])?;
 
@@ -193,28 +287,24 @@ impl super::USRP {
 		Ok(rx_streamer)
 	}
 
-	pub fn get_rx_stream(&mut self, args:&str, chans: &[size_t]) -> Result<RxStreamer, &'static str> {
+	pub fn get_rx_stream(&mut self, args:impl Into<DeviceArgs>, chans: &[size_t]) -> Result<RxStreamer, &'static str> {
+		let stream_args = StreamArgsBuilder { args: args.into(), ..StreamArgsBuilder::new() }.channels(chans);
+		self.rx_stream(&stream_args)
+	}
+
+	pub fn rx_stream(&mut self, stream_args:&StreamArgsBuilder) -> Result<RxStreamer, &'static str> {
 		// Note: This implementation assumes that you always want to create a new RxStreamer for every stream you want
 		// to create.  If you're going to be creating and destroying streams all the time, it might be more efficient to
 		// reuse instances of an RxStreamer.  If that ends up being the case, we could potentially create some kind of 
 		// pool of them inside the USRP struct and still provide the same abstraction to the outside
-		let otw_format = CString::new("sc16").unwrap();
-		let cpu_format = CString::new("sc16").unwrap();
-
-		let args_cstr = CString::new(args).unwrap();
-
-		let stream_args = StreamArgs {
-		    cpu_format:cpu_format.as_ptr(),		// Format of host memory
-		    otw_format:otw_format.as_ptr(),		// Over-the-wire format
-		    args:args_cstr.as_ptr(),			// Other stream args
-		    channel_list: chans.as_ptr(),   	// Array that lists channels
-		    n_channels: chans.len() as isize	// Number of channels
-		};
-
-		let mut rx_streamer = RxStreamer::new(stream_args.n_channels as usize)?;
-		if unsafe { uhd_usrp_get_rx_stream(self.handle, &stream_args, rx_streamer.get_handle()) } != 0 {
-			return Err("Unable to get an RxStream");
-		}
+
+		let mut rx_streamer = RxStreamer::new(stream_args.channels.len())?;
+		stream_args.with_c_args(|c_args| {
+			match unsafe { uhd_usrp_get_rx_stream(self.handle, c_args, rx_streamer.get_handle()) } {
+				0 => Ok(()),
+				_ => Err("Unable to get an RxStream")
+			}
+		})?;
 
 		rx_streamer.get_max_num_samps()?;
 
@@ -240,7 +330,32 @@ impl super::USRP {
 		check_err(string_vec.get_rust_vec()?, result)
 	} 
 
+	pub fn get_rx_antenna(&self, chan:usize) -> Result<String, &'static str> {
+		let mut buff: Vec<u8> = vec![0; 64];
+		let result = unsafe { uhd_usrp_get_rx_antenna(self.handle, chan, buff.as_mut_ptr(), buff.len()) };
+		check_err((), result)?;
+		cstr_from_buff(&buff)
+	}
+
+	pub fn get_rx_gain_names(&self, chan:usize) -> Result<Vec<String>, &'static str> {
+		let mut string_vec = StringVector::new()?;
+		let result = unsafe { uhd_usrp_get_rx_gain_names(self.handle, chan, &mut string_vec.handle) };
+		check_err(string_vec.get_rust_vec()?, result)
+	}
+
+	pub fn get_rx_gain_range(&self, chan:usize, gain_name:&str) -> Result<MetaRange, &'static str> {
+		let gain_name_c:CString = CString::new(gain_name).map_err(|_| "Unable to represent `gain_name` as a CString")?;
+		let range = MetaRange::new()?;
+		let result = unsafe { uhd_usrp_get_rx_gain_range(self.handle, gain_name_c.as_ptr(), chan, range.handle) };
+		check_err(range, result)
+	}
+
 	// Get or set configuration values
+	pub fn set_rx_antenna(&mut self, ant:&str, chan:usize) -> Result<(), &'static str> {
+		let ant_c:CString = CString::new(ant).map_err(|_| "Unable to represent `ant` as a CString")?;
+		check_err((), unsafe { uhd_usrp_set_rx_antenna(self.handle, ant_c.as_ptr(), chan) })
+	}
+
 	pub fn set_rx_rate(&mut self, rate:f64, chan:usize) -> Result<(), &'static str> {
 		check_err((), unsafe { uhd_usrp_set_rx_rate(self.handle, rate, chan) })
 	}
@@ -263,6 +378,54 @@ impl super::USRP {
 		check_err(gain_out, result)
 	}
 
+	pub fn set_normalized_rx_gain(&mut self, gain:f64, chan:usize) -> Result<(), &'static str> {
+		if !(0.0..=1.0).contains(&gain) {
+			return Err("Normalized gain must be between 0.0 and 1.0");
+		}
+		check_err((), unsafe { uhd_usrp_set_normalized_rx_gain(self.handle, gain, chan) })
+	}
+
+	pub fn get_normalized_rx_gain(&self, chan:usize) -> Result<f64, &'static str> {
+		let mut gain_out:f64 = 0.0;
+		let result = unsafe { uhd_usrp_get_normalized_rx_gain(self.handle, chan, &mut gain_out) };
+		check_err(gain_out, result)
+	}
+
+	// Splits `total_gain` across the named gain elements in the order UHD reports them, sets each element
+	// and returns the value applied to each one
+	pub fn set_rx_gain_distributed(&mut self, total_gain:f64, chan:usize) -> Result<Vec<(String, f64)>, &'static str> {
+		let names = self.get_rx_gain_names(chan)?;
+		let names:Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+		self.set_rx_gain_elements(total_gain, chan, &names)
+	}
+
+	// Same as `set_rx_gain_distributed`, but the caller chooses which elements take gain first
+	pub fn set_rx_gain_elements(&mut self, total_gain:f64, chan:usize, order:&[&str]) -> Result<Vec<(String, f64)>, &'static str> {
+		let mut elements:Vec<(String, Range)> = vec![];
+		for name in order {
+			elements.push((name.to_string(), self.get_rx_gain_range(chan, name)?.overall()?));
+		}
+
+		let distribution = gain::distribute(total_gain, &elements);
+		for (name, gain) in distribution.iter() {
+			self.set_rx_gain(*gain, chan, name)?;
+		}
+
+		Ok(distribution)
+	}
+
+	pub fn set_rx_agc(&mut self, enable:bool, chan:usize) -> Result<(), &'static str> {
+		check_err((), unsafe { uhd_usrp_set_rx_agc(self.handle, enable, chan) })
+	}
+
+	pub fn set_rx_dc_offset_enabled(&mut self, enable:bool, chan:usize) -> Result<(), &'static str> {
+		check_err((), unsafe { uhd_usrp_set_rx_dc_offset_enabled(self.handle, enable, chan) })
+	}
+
+	pub fn set_rx_iq_balance_enabled(&mut self, enable:bool, chan:usize) -> Result<(), &'static str> {
+		check_err((), unsafe { uhd_usrp_set_rx_iq_balance_enabled(self.handle, enable, chan) })
+	}
+
 	pub fn set_rx_freq(&mut self, tune_request:&TuneRequest, chan:usize) -> Result<TuneResult, &'static str> {
 		let mut tune_result:TuneResult = TuneResult::default();
 		let result = unsafe { uhd_usrp_set_rx_freq(self.handle, tune_request, chan, &mut tune_result) };
@@ -270,16 +433,11 @@ impl super::USRP {
 	}
 
 	pub fn set_rx_freq_auto(&mut self, freq_hz:f64, chan:usize) -> Result<TuneResult, &'static str> {
-		let args = CString::new("").unwrap();
-		let tune_request = TuneRequest {
-		    target_freq:    freq_hz,					// Target frequency for RF chain in Hz
-		    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-		    rf_freq: 		0.0,						// RF frequency in Hz
-		    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-		    dsp_freq:		0.0,						// DSP frequency in Hz
-		    args:args.as_ptr()							// Key-value pairs delimited by commas		
-		};
-		self.set_rx_freq(&tune_request, chan)
+		self.tune_rx(&TuneRequestBuilder::new(freq_hz), chan)
+	}
+
+	pub fn tune_rx(&mut self, request:&TuneRequestBuilder, chan:usize) -> Result<TuneResult, &'static str> {
+		request.with_c_request(|tune_request| self.set_rx_freq(tune_request, chan))
 	}
 
 	pub fn get_rx_freq(&self, chan:usize) -> Result<f64, &'static str> {
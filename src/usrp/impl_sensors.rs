@@ -13,14 +13,20 @@ extern {
     fn uhd_usrp_get_mboard_sensor(h:usize, name:*const c_char, mboard:usize, sensor_value_out:*mut SensorValueHandle) -> UhdError;
     fn uhd_usrp_get_mboard_sensor_names(h:usize, mboard:size_t, mboard_sensor_names_out:&mut usize) -> UhdError;
 
+    fn uhd_usrp_get_rx_sensor(h:usize, name:*const c_char, chan:size_t, sensor_value_out:*mut SensorValueHandle) -> UhdError;
+    fn uhd_usrp_get_rx_sensor_names(h:usize, chan:size_t, sensor_names_out:&mut usize) -> UhdError;
+
+    fn uhd_usrp_get_tx_sensor(h:usize, name:*const c_char, chan:size_t, sensor_value_out:*mut SensorValueHandle) -> UhdError;
+    fn uhd_usrp_get_tx_sensor_names(h:usize, chan:size_t, sensor_names_out:&mut usize) -> UhdError;
+
 
 }
 
 impl USRP {
 
-    pub fn get_mboard_sensor(&self, name:&str, mboard:usize) -> Result<SensorValue, &'static str> {
+    pub fn get_mboard_sensor<S: AsRef<str>>(&self, name:S, mboard:usize) -> Result<SensorValue, &'static str> {
         let mut ans = SensorValue::new()?;
-        let name_c = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+        let name_c = CString::new(name.as_ref()).map_err(|_| "Unable to represent `name` as a CString")?;
         match unsafe { uhd_usrp_get_mboard_sensor(self.handle, name_c.as_ptr(), mboard, ans.as_mut_ptr()) } {
             0 => Ok(ans),
             _ => Err("Unable to get motherboard sensor"),
@@ -35,6 +41,40 @@ impl USRP {
         }
     }
 
+    pub fn get_rx_sensor<S: AsRef<str>>(&self, name:S, chan:usize) -> Result<SensorValue, &'static str> {
+        let mut ans = SensorValue::new()?;
+        let name_c = CString::new(name.as_ref()).map_err(|_| "Unable to represent `name` as a CString")?;
+        match unsafe { uhd_usrp_get_rx_sensor(self.handle, name_c.as_ptr(), chan, ans.as_mut_ptr()) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get RX sensor"),
+        }
+    }
+
+    pub fn get_rx_sensor_names(&self, chan:usize) -> Result<Vec<String>, &'static str> {
+        let mut string_vec = StringVector::new()?;
+        match unsafe { uhd_usrp_get_rx_sensor_names(self.handle, chan, &mut string_vec.handle) } {
+            0 => Ok(string_vec.get_rust_vec()?),
+            _ => Err("Unable to get RX sensor names")
+        }
+    }
+
+    pub fn get_tx_sensor<S: AsRef<str>>(&self, name:S, chan:usize) -> Result<SensorValue, &'static str> {
+        let mut ans = SensorValue::new()?;
+        let name_c = CString::new(name.as_ref()).map_err(|_| "Unable to represent `name` as a CString")?;
+        match unsafe { uhd_usrp_get_tx_sensor(self.handle, name_c.as_ptr(), chan, ans.as_mut_ptr()) } {
+            0 => Ok(ans),
+            _ => Err("Unable to get TX sensor"),
+        }
+    }
+
+    pub fn get_tx_sensor_names(&self, chan:usize) -> Result<Vec<String>, &'static str> {
+        let mut string_vec = StringVector::new()?;
+        match unsafe { uhd_usrp_get_tx_sensor_names(self.handle, chan, &mut string_vec.handle) } {
+            0 => Ok(string_vec.get_rust_vec()?),
+            _ => Err("Unable to get TX sensor names")
+        }
+    }
+
 
 
 }
\ No newline at end of file
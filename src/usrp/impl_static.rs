@@ -1,7 +1,8 @@
 
-use std::ffi::CString;
 use libc::c_char;
 
+use crate::types::device_args::DeviceArgs;
+use crate::types::device_info::DeviceInfo;
 use crate::types::string_vector::StringVector;
 
 #[link(name = "uhd")]
@@ -12,9 +13,9 @@ extern {
 
 impl super::USRP {
 
-	pub fn find(args:&str) -> Result<Vec<String>, &'static str> {
+	pub fn find(args:impl Into<DeviceArgs>) -> Result<Vec<String>, &'static str> {
 
-		let args = CString::new(args).map_err(|_| "Unable to create CString; check for null characters")?;
+		let args = args.into().to_cstring()?;
 		let mut string_vec = StringVector::new()?;
 		match unsafe { uhd_usrp_find(args.as_ptr(), &mut string_vec.handle) } {
 			0 => Ok(string_vec.get_rust_vec()?),
@@ -23,9 +24,20 @@ impl super::USRP {
 
 	}
 
-	pub fn new(args:&str) -> Result<Self, &'static str> {
+	// Same as `find` but with each result parsed, in an order that doesn't depend on the order of discovery
+	pub fn find_devices(args:impl Into<DeviceArgs>) -> Result<Vec<DeviceInfo>, &'static str> {
+		let mut devices = Self::find(args)?.iter().map(|s| s.parse()).collect::<Result<Vec<DeviceInfo>, _>>()?;
+		DeviceInfo::sort(&mut devices);
+		Ok(devices)
+	}
+
+	pub fn open(info:&DeviceInfo) -> Result<Self, &'static str> {
+		Self::new(info.open_args())
+	}
+
+	pub fn new(args:impl Into<DeviceArgs>) -> Result<Self, &'static str> {
 
-		let args = CString::new(args).map_err(|_| "Unable to create CString; check for null characters")?;
+		let args = args.into().to_cstring()?;
 
 		let mut handle:usize = 0;
 
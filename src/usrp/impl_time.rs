@@ -13,11 +13,11 @@ extern {
     fn uhd_usrp_get_time_now(h:usize, mboard:size_t, full_secs_out:&mut i64, frac_secs_out:&mut f64) -> UhdError;
     fn uhd_usrp_get_time_last_pps(h:usize, mboard:usize, full_secs_out:&mut i64, frac_secs_out:&mut f64) -> UhdError;
 
-    // uhd_error uhd_usrp_set_time_now(uhd_usrp_handle h, int64_t full_secs, double frac_secs, size_t mboard)
+    fn uhd_usrp_set_time_now(h:usize, full_secs:i64, frac_secs:f64, mboard:size_t) -> UhdError;
     fn uhd_usrp_set_time_next_pps(h:usize, full_secs:i64, frac_secs:f64, mboard:usize) -> UhdError;
     fn uhd_usrp_set_time_unknown_pps(h:usize, full_secs:i64, frac_secs:f64) -> UhdError;
 
-    // uhd_error uhd_usrp_get_time_synchronized(uhd_usrp_handle h, bool *result_out)
+    fn uhd_usrp_get_time_synchronized(h:usize, result_out:&mut bool) -> UhdError;
     
     fn uhd_usrp_set_command_time(h:usize, full_secs:i64, frac_secs:f64, mboard:size_t) -> UhdError;
     fn uhd_usrp_clear_command_time(h:usize, mboard:size_t) -> UhdError;
@@ -58,6 +58,13 @@ impl USRP {
         }
     }
 
+    pub fn set_time_now(&mut self, full_secs:i64, frac_secs:f64, mboard:usize) -> Result<(), &'static str> {
+        match unsafe { uhd_usrp_set_time_now(self.handle, full_secs, frac_secs, mboard) } {
+            0 => Ok(()),
+            _ => Err("Unable to set time now"),
+        }
+    }
+
     pub fn set_time_next_pps(&mut self, full_secs:i64, frac_secs:f64, mboard:usize) -> Result<(), &'static str> {
         match unsafe { uhd_usrp_set_time_next_pps(self.handle, full_secs, frac_secs, mboard) } {
             0 => Ok(()),
@@ -79,6 +86,13 @@ impl USRP {
         check_err((full_secs_out, frac_secs_out), result)
     }
 
+    // True if the times on all mboards are within a small tolerance of each other
+    pub fn get_time_synchronized(&self) -> Result<bool, &'static str> {
+        let mut ans = false;
+        let result = unsafe { uhd_usrp_get_time_synchronized(self.handle, &mut ans) };
+        check_err(ans, result)
+    }
+
     pub fn get_time_source(&self, mboard:usize) -> Result<String, &'static str> {
         let buffer_init = "                                        ";
         let cstr_ans:CString = CString::new(buffer_init).map_err(|_| "Unable to create CString")?;
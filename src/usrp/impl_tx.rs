@@ -4,40 +4,44 @@ use std::ffi::CString;
 
 use libc::{c_char, size_t};
 
+use crate::c_interop::{collect_cstr, cstr_from_buff};
 use crate::tx_streamer::TxStreamer;
-use crate::types::{TuneRequest, TuneResult, TuneRequestPolicy};
+use crate::types::{TuneRequest, TuneResult};
+use crate::types::device_args::DeviceArgs;
+use crate::types::ranges::{MetaRange, Range};
+use crate::types::stream_args::{OtwFormat, StreamArgsBuilder};
 use crate::types::string_vector::StringVector;
+use crate::types::tune_request::TuneRequestBuilder;
 use crate::types::usrp_info::Info;
-use crate::usrp::StreamArgs;
+use crate::usrp::{gain, StreamArgs};
+use crate::usrp::subdev_spec::SubdevSpec;
 
 #[link(name = "uhd")]
 extern {
 
-	// uhd_error uhd_usrp_set_tx_subdev_spec(uhd_usrp_handle h, uhd_subdev_spec_handle subdev_spec, size_t mboard)
-	// uhd_error uhd_usrp_get_tx_subdev_spec(uhd_usrp_handle h, size_t mboard, uhd_subdev_spec_handle subdev_spec_out)
-	// uhd_error uhd_usrp_get_tx_subdev_name(uhd_usrp_handle h, size_t chan, char* tx_subdev_name_out, size_t strbuffer_len)
-	// uhd_error uhd_usrp_get_tx_rates(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle rates_out)
-	// uhd_error uhd_usrp_get_tx_freq_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle freq_range_out)
-	// uhd_error uhd_usrp_get_fe_tx_freq_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle freq_range_out)
-	// uhd_error uhd_usrp_get_tx_lo_names(uhd_usrp_handle h, size_t chan, uhd_string_vector_handle *tx_lo_names_out)
-	// uhd_error uhd_usrp_set_tx_lo_source(uhd_usrp_handle h, const char* src, const char* name, size_t chan)
-	// uhd_error uhd_usrp_get_tx_lo_source(uhd_usrp_handle h, const char* name, size_t chan, char* tx_lo_source_out, size_t strbuffer_len)
-	// uhd_error uhd_usrp_get_tx_lo_sources(uhd_usrp_handle h, const char* name, size_t chan, uhd_string_vector_handle *tx_lo_sources_out)
-	// uhd_error uhd_usrp_set_tx_lo_export_enabled(uhd_usrp_handle h, bool enabled, const char* name, size_t chan)
-	// uhd_error uhd_usrp_get_tx_lo_export_enabled(uhd_usrp_handle h, const char* name, size_t chan, bool* result_out)
-	// uhd_error uhd_usrp_set_tx_lo_freq(uhd_usrp_handle h, double freq, const char* name, size_t chan, double* coerced_freq_out)
-	// uhd_error uhd_usrp_get_tx_lo_freq(uhd_usrp_handle h, const char* name, size_t chan, double* tx_lo_freq_out)
-	// uhd_error uhd_usrp_set_normalized_tx_gain(uhd_usrp_handle h, double gain, size_t chan)
-	// uhd_error uhd_usrp_get_tx_gain_range(uhd_usrp_handle h, const char* name, size_t chan, uhd_meta_range_handle gain_range_out)
-	// uhd_error uhd_usrp_get_normalized_tx_gain(uhd_usrp_handle h, size_t chan, double *gain_out)
-	// uhd_error uhd_usrp_get_tx_gain_names(uhd_usrp_handle h, size_t chan, uhd_string_vector_handle *gain_names_out)
-	// uhd_error uhd_usrp_set_tx_antenna(uhd_usrp_handle h, const char* ant, size_t chan)
-	// uhd_error uhd_usrp_get_tx_antenna(uhd_usrp_handle h, size_t chan, char* ant_out, size_t strbuffer_len)
-	// uhd_error uhd_usrp_set_tx_bandwidth(uhd_usrp_handle h, double bandwidth, size_t chan)
-	// uhd_error uhd_usrp_get_tx_bandwidth(uhd_usrp_handle h, size_t chan, double *bandwidth_out)
-	// uhd_error uhd_usrp_get_tx_bandwidth_range(uhd_usrp_handle h, size_t chan, uhd_meta_range_handle bandwidth_range_out)
-	// uhd_error uhd_usrp_get_tx_sensor(uhd_usrp_handle h, const char* name, size_t chan, uhd_sensor_value_handle *sensor_value_out)
-	// uhd_error uhd_usrp_get_tx_sensor_names(uhd_usrp_handle h, size_t chan, uhd_string_vector_handle *sensor_names_out)
+	fn uhd_usrp_set_tx_subdev_spec(h:usize, subdev_spec:usize, mboard:size_t) -> isize;
+	fn uhd_usrp_get_tx_subdev_spec(h:usize, mboard:size_t, subdev_spec_out:usize) -> isize;
+	fn uhd_usrp_get_tx_subdev_name(h:usize, chan:size_t, tx_subdev_name_out:*mut u8, strbuffer_len:size_t) -> isize;
+	fn uhd_usrp_get_tx_rates(h:usize, chan:size_t, rates_out:usize) -> isize;
+	fn uhd_usrp_get_tx_freq_range(h:usize, chan:size_t, freq_range_out:usize) -> isize;
+	fn uhd_usrp_get_fe_tx_freq_range(h:usize, chan:size_t, freq_range_out:usize) -> isize;
+	fn uhd_usrp_get_tx_lo_names(h:usize, chan:size_t, tx_lo_names_out:&mut usize) -> isize;
+	fn uhd_usrp_set_tx_lo_source(h:usize, src:*const c_char, name:*const c_char, chan:size_t) -> isize;
+	fn uhd_usrp_get_tx_lo_source(h:usize, name:*const c_char, chan:size_t, tx_lo_source_out:*mut u8, strbuffer_len:size_t) -> isize;
+	fn uhd_usrp_get_tx_lo_sources(h:usize, name:*const c_char, chan:size_t, tx_lo_sources_out:&mut usize) -> isize;
+	fn uhd_usrp_set_tx_lo_export_enabled(h:usize, enabled:bool, name:*const c_char, chan:size_t) -> isize;
+	fn uhd_usrp_get_tx_lo_export_enabled(h:usize, name:*const c_char, chan:size_t, result_out:&mut bool) -> isize;
+	fn uhd_usrp_set_tx_lo_freq(h:usize, freq:f64, name:*const c_char, chan:size_t, coerced_freq_out:&mut f64) -> isize;
+	fn uhd_usrp_get_tx_lo_freq(h:usize, name:*const c_char, chan:size_t, tx_lo_freq_out:&mut f64) -> isize;
+	fn uhd_usrp_set_normalized_tx_gain(h:usize, gain:f64, chan:size_t) -> isize;
+	fn uhd_usrp_get_tx_gain_range(h:usize, name:*const c_char, chan:size_t, gain_range_out:usize) -> isize;
+	fn uhd_usrp_get_normalized_tx_gain(h:usize, chan:size_t, gain_out:&mut f64) -> isize;
+	fn uhd_usrp_get_tx_gain_names(h:usize, chan:size_t, gain_names_out:&mut usize) -> isize;
+	fn uhd_usrp_set_tx_antenna(h:usize, ant:*const c_char, chan:size_t) -> isize;
+	fn uhd_usrp_get_tx_antenna(h:usize, chan:size_t, ant_out:*mut u8, strbuffer_len:size_t) -> isize;
+	fn uhd_usrp_set_tx_bandwidth(h:usize, bandwidth:f64, chan:size_t) -> isize;
+	fn uhd_usrp_get_tx_bandwidth(h:usize, chan:size_t, bandwidth_out:&mut f64) -> isize;
+	fn uhd_usrp_get_tx_bandwidth_range(h:usize, chan:size_t, bandwidth_range_out:usize) -> isize;
 
 	fn uhd_usrp_get_tx_info(h:usize, chan:size_t, info_out:&mut Info) -> isize;
 	fn uhd_usrp_get_tx_antennas(h:usize, chan:size_t, antennas_out:&mut usize) -> isize;
@@ -56,35 +60,31 @@ extern {
 
 impl super::USRP {
 
-	pub fn get_tx_stream<W: Any, U: Any>(&mut self, args:&str) -> Result<TxStreamer, &'static str> {
+	pub fn get_tx_stream<W: Any, U: Any>(&mut self, args:impl Into<DeviceArgs>) -> Result<TxStreamer, &'static str> {
 
 		let otw_format = match TypeId::of::<W>() {
-			id if id == TypeId::of::<i16>() => CString::new("sc16").unwrap(),
+			id if id == TypeId::of::<i16>() => OtwFormat::Sc16,
 			_ => return Err("Unsupported type for wire format")
 		};
-		let cpu_format = match TypeId::of::<U>() {
-			id if id == TypeId::of::<i16>() => CString::new("sc16").unwrap(),
-			id if id == TypeId::of::<f32>() => CString::new("fc32").unwrap(),
-			_ => return Err("Unsupported type for CPU format")
-		};
-
-		let args_cstr = CString::new(args).unwrap();
+		// TxStreamer only writes from sc16 buffers
+		if TypeId::of::<U>() != TypeId::of::<i16>() {
+			return Err("Unsupported type for CPU format");
+		}
 
-		// We only support one channel per stream right now
-		let channel = 0;
+		let stream_args = StreamArgsBuilder { args: args.into(), ..StreamArgsBuilder::new() }
+			.otw_format(otw_format);
 
-		let stream_args = StreamArgs {
-		    cpu_format:cpu_format.as_ptr(),	// Format of host memory
-		    otw_format:otw_format.as_ptr(),	// Over-the-wire format		
-		    args:args_cstr.as_ptr(),		// Other stream args
-		    channel_list:&channel, 			// Array that lists channels
-		    n_channels:1					// Number of channels
-		};
+		self.tx_stream(&stream_args)
+	}
 
-		let mut tx_streamer = TxStreamer::new(stream_args.n_channels as usize)?;
-		if unsafe { uhd_usrp_get_tx_stream(self.handle, &stream_args, tx_streamer.get_handle()) } != 0 {
-			return Err("Unable to get an TxStream");
-		}
+	pub fn tx_stream(&mut self, stream_args:&StreamArgsBuilder) -> Result<TxStreamer, &'static str> {
+		let mut tx_streamer = TxStreamer::new(stream_args.channels.len())?;
+		stream_args.with_c_args(|c_args| {
+			match unsafe { uhd_usrp_get_tx_stream(self.handle, c_args, tx_streamer.get_handle()) } {
+				0 => Ok(()),
+				_ => Err("Unable to get an TxStream")
+			}
+		})?;
 
 		tx_streamer.get_max_num_samps()?;
 
@@ -115,6 +115,22 @@ impl super::USRP {
 		}
 	}
 
+	pub fn set_tx_antenna(&mut self, ant:&str, chan:usize) -> Result<(), &'static str> {
+		let ant_c:CString = CString::new(ant).map_err(|_| "Unable to represent `ant` as a CString")?;
+		match unsafe { uhd_usrp_set_tx_antenna(self.handle, ant_c.as_ptr(), chan) } {
+			0 => Ok(()),
+			_ => Err("Unable to set TX antenna")
+		}
+	}
+
+	pub fn get_tx_antenna(&self, chan:usize) -> Result<String, &'static str> {
+		let mut buff:Vec<u8> = vec![0; 64];
+		match unsafe { uhd_usrp_get_tx_antenna(self.handle, chan, buff.as_mut_ptr(), buff.len()) } {
+			0 => cstr_from_buff(&buff),
+			_ => Err("Unable to get TX antenna")
+		}
+	}
+
 	pub fn set_tx_rate(&mut self, rate:f64, chan:usize) -> Result<(), &'static str> {
 		match unsafe { uhd_usrp_set_tx_rate(self.handle, rate, chan) } {
 			0 => Ok(()),
@@ -139,16 +155,11 @@ impl super::USRP {
 	}
 
 	pub fn set_tx_freq_auto(&mut self, freq_hz:f64, chan:usize) -> Result<TuneResult, &'static str> {
-		let args = CString::new("").unwrap();
-		let tune_request = TuneRequest {
-		    target_freq:    freq_hz,					// Target frequency for RF chain in Hz
-		    rf_freq_policy: TuneRequestPolicy::Auto, 	// RF frequency policy
-		    rf_freq: 		0.0,						// RF frequency in Hz
-		    dsp_freq_policy:TuneRequestPolicy::Auto, 	// DSP frequency policy
-		    dsp_freq:		0.0,						// DSP frequency in Hz
-		    args:args.as_ptr()							// Key-value pairs delimited by commas		
-		};
-		self.set_tx_freq(&tune_request, chan)
+		self.tune_tx(&TuneRequestBuilder::new(freq_hz), chan)
+	}
+
+	pub fn tune_tx(&mut self, request:&TuneRequestBuilder, chan:usize) -> Result<TuneResult, &'static str> {
+		request.with_c_request(|tune_request| self.set_tx_freq(tune_request, chan))
 	}
 
 	pub fn get_tx_freq(&self, chan:usize) -> Result<f64, &'static str> {
@@ -177,4 +188,237 @@ impl super::USRP {
 	}
 
 
-}
\ No newline at end of file
+	pub fn get_tx_gain_names(&self, chan:usize) -> Result<Vec<String>, &'static str> {
+		let mut string_vec = StringVector::new()?;
+		match unsafe { uhd_usrp_get_tx_gain_names(self.handle, chan, &mut string_vec.handle) } {
+			0 => Ok(string_vec.get_rust_vec()?),
+			_ => Err("Unable to retrieve TX gain names")
+		}
+	}
+
+	pub fn get_tx_gain_range(&self, chan:usize, gain_name:&str) -> Result<MetaRange, &'static str> {
+		let gain_name_c:CString = CString::new(gain_name).map_err(|_| "Unable to represent `gain_name` as a CString")?;
+		let range = MetaRange::new()?;
+		match unsafe { uhd_usrp_get_tx_gain_range(self.handle, gain_name_c.as_ptr(), chan, range.handle) } {
+			0 => Ok(range),
+			_ => Err("Unable to get TX gain range")
+		}
+	}
+
+	pub fn set_normalized_tx_gain(&mut self, gain:f64, chan:usize) -> Result<(), &'static str> {
+		if !(0.0..=1.0).contains(&gain) {
+			return Err("Normalized gain must be between 0.0 and 1.0");
+		}
+		match unsafe { uhd_usrp_set_normalized_tx_gain(self.handle, gain, chan) } {
+			0 => Ok(()),
+			_ => Err("Unable to set normalized TX gain")
+		}
+	}
+
+	pub fn get_normalized_tx_gain(&self, chan:usize) -> Result<f64, &'static str> {
+		let mut gain_out:f64 = 0.0;
+		match unsafe { uhd_usrp_get_normalized_tx_gain(self.handle, chan, &mut gain_out) } {
+			0 => Ok(gain_out),
+			_ => Err("Unable to get normalized TX gain")
+		}
+	}
+
+	// Splits `total_gain` across the named gain elements in the order UHD reports them, sets each element
+	// and returns the value applied to each one
+	pub fn set_tx_gain_distributed(&mut self, total_gain:f64, chan:usize) -> Result<Vec<(String, f64)>, &'static str> {
+		let names = self.get_tx_gain_names(chan)?;
+		let names:Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+		self.set_tx_gain_elements(total_gain, chan, &names)
+	}
+
+	// Same as `set_tx_gain_distributed`, but the caller chooses which elements take gain first
+	pub fn set_tx_gain_elements(&mut self, total_gain:f64, chan:usize, order:&[&str]) -> Result<Vec<(String, f64)>, &'static str> {
+		let mut elements:Vec<(String, Range)> = vec![];
+		for name in order {
+			elements.push((name.to_string(), self.get_tx_gain_range(chan, name)?.overall()?));
+		}
+
+		let distribution = gain::distribute(total_gain, &elements);
+		for (name, gain) in distribution.iter() {
+			self.set_tx_gain(*gain, chan, name)?;
+		}
+
+		Ok(distribution)
+	}
+
+	pub fn get_tx_subdev_spec(&self, mboard:usize) -> Result<SubdevSpec, &'static str> {
+		let spec = SubdevSpec::new("A0")?;
+		match unsafe { uhd_usrp_get_tx_subdev_spec(self.handle, mboard, spec.handle) } {
+			0 => Ok(spec),
+			_ => Err("Unable to get TX subdev spec")
+		}
+	}
+
+	// The device rejects frontends it doesn't have; reading the spec back catches any it silently remaps.
+	// With `ALL_MBOARDS` every mboard is read back.
+	pub fn set_tx_subdev_spec(&mut self, spec:&SubdevSpec, mboard:usize) -> Result<(), &'static str> {
+		spec.validate()?;
+		if unsafe { uhd_usrp_set_tx_subdev_spec(self.handle, spec.handle, mboard) } != 0 {
+			return Err("Unable to set TX subdev spec");
+		}
+		for mboard in self.mboards(mboard)? {
+			if !spec.matches(&self.get_tx_subdev_spec(mboard)?)? {
+				return Err("Device did not accept the TX subdev spec as given");
+			}
+		}
+		Ok(())
+	}
+
+	// Sets the spec to check that the device takes it, then puts back what every affected mboard had before, so the
+	// channel mapping is unchanged afterwards.  An error from the attempt takes priority over one from restoring.
+	pub fn try_tx_subdev_spec(&mut self, spec:&SubdevSpec, mboard:usize) -> Result<(), &'static str> {
+		let previous = self.mboards(mboard)?
+			.map(|mboard| Ok((mboard, self.get_tx_subdev_spec(mboard)?)))
+			.collect::<Result<Vec<(usize, SubdevSpec)>, &'static str>>()?;
+		let ans = self.set_tx_subdev_spec(spec, mboard);
+
+		let mut restored = Ok(());
+		for (mboard, spec) in previous.iter() {
+			if unsafe { uhd_usrp_set_tx_subdev_spec(self.handle, spec.handle, *mboard) } != 0 {
+				restored = Err("Unable to restore TX subdev spec");
+			}
+		}
+		ans.and(restored)
+	}
+
+	pub fn get_tx_subdev_name(&self, chan:usize) -> Result<String, &'static str> {
+		let mut buff:Vec<u8> = vec![0; 128];
+		match unsafe { uhd_usrp_get_tx_subdev_name(self.handle, chan, buff.as_mut_ptr(), buff.len()) } {
+			0 => Ok(unsafe { collect_cstr(buff.as_ptr()) }),
+			_ => Err("Unable to get TX subdev name")
+		}
+	}
+
+	pub fn get_tx_rates(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		match unsafe { uhd_usrp_get_tx_rates(self.handle, chan, range.handle) } {
+			0 => Ok(range),
+			_ => Err("Unable to get TX rates")
+		}
+	}
+
+	pub fn get_tx_freq_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		match unsafe { uhd_usrp_get_tx_freq_range(self.handle, chan, range.handle) } {
+			0 => Ok(range),
+			_ => Err("Unable to get TX freq range")
+		}
+	}
+
+	pub fn get_fe_tx_freq_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		match unsafe { uhd_usrp_get_fe_tx_freq_range(self.handle, chan, range.handle) } {
+			0 => Ok(range),
+			_ => Err("Unable to get TX frontend freq range")
+		}
+	}
+
+	pub fn get_tx_lo_names(&self, chan:usize) -> Result<StringVector, &'static str> {
+		let mut sv = StringVector::new()?;
+		match unsafe { uhd_usrp_get_tx_lo_names(self.handle, chan, &mut sv.handle) } {
+			0 => Ok(sv),
+			_ => Err("Unable to get TX LO names")
+		}
+	}
+
+	pub fn set_tx_lo_source(&self, src:&str, name:&str, chan:usize) -> Result<(), &'static str> {
+		let src_c:CString = CString::new(src).map_err(|_| "Unable to represent `src` as a CString")?;
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		match unsafe { uhd_usrp_set_tx_lo_source(self.handle, src_c.as_ptr(), name_c.as_ptr(), chan) } {
+			0 => Ok(()),
+			_ => {
+				eprintln!("{:?}", self.last_error());
+				Err("Unable to set TX LO source")
+			}
+		}
+	}
+
+	pub fn get_tx_lo_source(&self, name:&str, chan:usize) -> Result<String, &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		let mut buff:Vec<u8> = vec![0; 64];
+		match unsafe { uhd_usrp_get_tx_lo_source(self.handle, name_c.as_ptr(), chan, buff.as_mut_ptr(), buff.len()) } {
+			0 => cstr_from_buff(&buff),
+			_ => {
+				eprintln!("{:?}", self.last_error());
+				Err("Unable to get TX LO source")
+			}
+		}
+	}
+
+	pub fn get_tx_lo_sources(&self, name:&str, chan:usize) -> Result<StringVector, &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		let mut sv = StringVector::new()?;
+		match unsafe { uhd_usrp_get_tx_lo_sources(self.handle, name_c.as_ptr(), chan, &mut sv.handle) } {
+			0 => Ok(sv),
+			_ => Err("Unable to get TX LO sources")
+		}
+	}
+
+	pub fn set_tx_lo_export_enabled(&self, en:bool, name:&str, chan:usize) -> Result<(), &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		match unsafe { uhd_usrp_set_tx_lo_export_enabled(self.handle, en, name_c.as_ptr(), chan) } {
+			0 => Ok(()),
+			_ => {
+				eprintln!("{:?}", self.last_error());
+				Err("Unable to set TX LO export enabled")
+			}
+		}
+	}
+
+	pub fn get_tx_lo_export_enabled(&self, name:&str, chan:usize) -> Result<bool, &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		let mut enabled = false;
+		match unsafe { uhd_usrp_get_tx_lo_export_enabled(self.handle, name_c.as_ptr(), chan, &mut enabled) } {
+			0 => Ok(enabled),
+			_ => Err("Unable to get TX LO export enabled")
+		}
+	}
+
+	// Returns the frequency the LO was actually coerced to
+	pub fn set_tx_lo_freq(&mut self, freq:f64, name:&str, chan:usize) -> Result<f64, &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		let mut coerced:f64 = 0.0;
+		match unsafe { uhd_usrp_set_tx_lo_freq(self.handle, freq, name_c.as_ptr(), chan, &mut coerced) } {
+			0 => Ok(coerced),
+			_ => Err("Unable to set TX LO freq")
+		}
+	}
+
+	pub fn get_tx_lo_freq(&self, name:&str, chan:usize) -> Result<f64, &'static str> {
+		let name_c:CString = CString::new(name).map_err(|_| "Unable to represent `name` as a CString")?;
+		let mut freq_out:f64 = 0.0;
+		match unsafe { uhd_usrp_get_tx_lo_freq(self.handle, name_c.as_ptr(), chan, &mut freq_out) } {
+			0 => Ok(freq_out),
+			_ => Err("Unable to get TX LO freq")
+		}
+	}
+
+	pub fn set_tx_bandwidth(&mut self, bandwidth:f64, chan:usize) -> Result<(), &'static str> {
+		match unsafe { uhd_usrp_set_tx_bandwidth(self.handle, bandwidth, chan) } {
+			0 => Ok(()),
+			_ => Err("Unable to set TX bandwidth")
+		}
+	}
+
+	pub fn get_tx_bandwidth(&self, chan:usize) -> Result<f64, &'static str> {
+		let mut ans:f64 = 0.0;
+		match unsafe { uhd_usrp_get_tx_bandwidth(self.handle, chan, &mut ans) } {
+			0 => Ok(ans),
+			_ => Err("Unable to get TX bandwidth")
+		}
+	}
+
+	pub fn get_tx_bandwidth_range(&self, chan:usize) -> Result<MetaRange, &'static str> {
+		let range = MetaRange::new()?;
+		match unsafe { uhd_usrp_get_tx_bandwidth_range(self.handle, chan, range.handle) } {
+			0 => Ok(range),
+			_ => Err("Unable to get TX bandwidth range")
+		}
+	}
+
+}
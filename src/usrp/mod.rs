@@ -1,11 +1,16 @@
 
 use libc::{size_t, c_char};
-use crate::c_interop::collect_cstr;
+use crate::c_interop::{collect_cstr, cstr_from_buff};
 
 use crate::check_err;
 
+pub mod gain;
 pub mod subdev_spec;
 
+// Same value UHD uses for multi_usrp::ALL_MBOARDS
+pub const ALL_MBOARDS:usize = usize::MAX;
+
+mod impl_gpio;
 mod impl_sensors;
 mod impl_static;
 mod impl_time;
@@ -17,10 +22,10 @@ extern {
 
 	fn uhd_usrp_last_error(h: usize, error_out: *mut u8, strbuffer_len: size_t) -> isize;
 
-	// uhd_error uhd_usrp_set_master_clock_rate(uhd_usrp_handle h, double rate, size_t mboard)
-	// uhd_error uhd_usrp_get_master_clock_rate(uhd_usrp_handle h, size_t mboard, double *clock_rate_out)
-	// uhd_error uhd_usrp_get_pp_string(uhd_usrp_handle h, char* pp_string_out, size_t strbuffer_len)
-	// uhd_error uhd_usrp_get_mboard_name(uhd_usrp_handle h, size_t mboard, char* mboard_name_out, size_t strbuffer_len)
+	fn uhd_usrp_set_master_clock_rate(h:usize, rate:f64, mboard:size_t) -> isize;
+	fn uhd_usrp_get_master_clock_rate(h:usize, mboard:size_t, clock_rate_out:&mut f64) -> isize;
+	fn uhd_usrp_get_pp_string(h:usize, pp_string_out:*mut u8, strbuffer_len:size_t) -> isize;
+	fn uhd_usrp_get_mboard_name(h:usize, mboard:size_t, mboard_name_out:*mut u8, strbuffer_len:size_t) -> isize;
 
 	// uhd_error uhd_usrp_set_user_register(uhd_usrp_handle h, uint8_t addr, uint32_t data, size_t mboard)
 	// uhd_error uhd_usrp_get_mboard_eeprom(uhd_usrp_handle h, uhd_mboard_eeprom_handle mb_eeprom, size_t mboard)
@@ -28,9 +33,6 @@ extern {
 	// uhd_error uhd_usrp_get_dboard_eeprom(uhd_usrp_handle h, uhd_dboard_eeprom_handle db_eeprom, const char* unit, const char* slot, size_t mboard)
 	// uhd_error uhd_usrp_set_dboard_eeprom(uhd_usrp_handle h, uhd_dboard_eeprom_handle db_eeprom, const char* unit, const char* slot, size_t mboard)
 		
-	// uhd_error uhd_usrp_get_gpio_banks(uhd_usrp_handle h, size_t mboard, uhd_string_vector_handle *gpio_banks_out)
-	// uhd_error uhd_usrp_set_gpio_attr(uhd_usrp_handle h, const char* bank, const char* attr, uint32_t value, uint32_t mask, size_t mboard)
-	// uhd_error uhd_usrp_get_gpio_attr(uhd_usrp_handle h, const char* bank, const char* attr, size_t mboard, uint32_t *attr_out)
 	// uhd_error uhd_usrp_enumerate_registers(uhd_usrp_handle h, size_t mboard, uhd_string_vector_handle *registers_out)
 	// uhd_error uhd_usrp_get_register_info(uhd_usrp_handle h, const char* path, size_t mboard, uhd_usrp_register_info_t *register_info_out)
 	// uhd_error uhd_usrp_write_register(uhd_usrp_handle h, const char* path, uint32_t field, uint64_t value, size_t mboard)
@@ -46,12 +48,12 @@ pub struct USRP {
 }
 
 #[repr(C)]
-pub struct StreamArgs {
-    pub cpu_format:*const c_char,	// Format of host memory
-    pub otw_format:*const c_char,	// Over-the-wire format		
-    pub args:*const c_char,			// Other stream args
-    pub channel_list:*const size_t, // Array that lists channels
-    pub n_channels:isize			// Number of channels
+pub(crate) struct StreamArgs {
+    pub(crate) cpu_format:*const c_char,	// Format of host memory
+    pub(crate) otw_format:*const c_char,	// Over-the-wire format		
+    pub(crate) args:*const c_char,			// Other stream args
+    pub(crate) channel_list:*const size_t, // Array that lists channels
+    pub(crate) n_channels:isize			// Number of channels
 }
 
 #[repr(C)]
@@ -93,6 +95,39 @@ impl USRP {
 		check_err(ans, result)
 	}
 
+	// The mboards a per-mboard call with `mboard` applies to, expanding `ALL_MBOARDS`
+	pub(crate) fn mboards(&self, mboard:usize) -> Result<std::ops::Range<usize>, &'static str> {
+		match mboard {
+			ALL_MBOARDS => Ok(0..self.num_mboards()?),
+			_ => Ok(mboard..mboard + 1),
+		}
+	}
+
+	pub fn set_master_clock_rate(&mut self, rate:f64, mboard:usize) -> Result<(), &'static str> {
+		check_err((), unsafe { uhd_usrp_set_master_clock_rate(self.handle, rate, mboard) })
+	}
+
+	pub fn get_master_clock_rate(&self, mboard:usize) -> Result<f64, &'static str> {
+		let mut ans:f64 = 0.0;
+		let result = unsafe { uhd_usrp_get_master_clock_rate(self.handle, mboard, &mut ans) };
+		check_err(ans, result)
+	}
+
+	pub fn get_mboard_name(&self, mboard:usize) -> Result<String, &'static str> {
+		let mut buff: Vec<u8> = vec![0; 256];
+		unsafe {
+			check_err((), uhd_usrp_get_mboard_name(self.handle, mboard, buff.as_mut_ptr(), buff.len()))?;
+			Ok(collect_cstr(buff.as_ptr()))
+		}
+	}
+
+	pub fn get_pp_string(&self) -> Result<String, &'static str> {
+		// Multi-mboard strings run well past what `collect_cstr` looks at, so read up to the NUL in the whole buffer
+		let mut buff: Vec<u8> = vec![0; 4096];
+		check_err((), unsafe { uhd_usrp_get_pp_string(self.handle, buff.as_mut_ptr(), buff.len()) })?;
+		cstr_from_buff(&buff)
+	}
+
 	pub fn last_error(&self) -> Result<String, &'static str> {
 		let mut buff: Vec<u8> = vec![0; 256];
 		unsafe {
@@ -1,7 +1,16 @@
-use std::ffi::CString;
-use libc::c_char;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::str::FromStr;
+
+use libc::{c_char, size_t};
 use crate::c_interop::collect_cstr;
 
+#[repr(C)]
+struct SubdevSpecPairC {
+    db_name: *mut c_char,
+    sd_name: *mut c_char,
+}
+
 #[link(name = "uhd")]
 extern {
 
@@ -10,12 +19,10 @@ extern {
 
     fn uhd_subdev_spec_size(h: usize, size_out: *mut isize) -> isize;
     fn uhd_subdev_spec_push_back(h: usize, markup: *const c_char) -> isize;
+    fn uhd_subdev_spec_at(h: usize, num: size_t, subdev_spec_pair_out: *mut SubdevSpecPairC) -> isize;
+    fn uhd_subdev_spec_pair_free(subdev_spec_pair: *mut SubdevSpecPairC) -> isize;
 
-    /*
-    UHD_API uhd_error uhd_subdev_spec_at(uhd_subdev_spec_handle h, size_t num, uhd_subdev_spec_pair_t* subdev_spec_pair_out);
-    UHD_API uhd_error uhd_subdev_spec_to_pp_string(uhd_subdev_spec_handle h, char* pp_string_out, size_t strbuffer_len);
-     */
-
+    fn uhd_subdev_spec_to_pp_string(h: usize, pp_string_out: *mut u8, strbuffer_len: usize) -> isize;
     fn uhd_subdev_spec_to_string(h: usize, string_out: *mut u8, strbuffer_len: usize) -> isize;
     fn uhd_subdev_spec_last_error(h: usize, error_out: *mut u8, strbuffer_len: usize) -> isize;
 }
@@ -23,6 +30,55 @@ extern {
 #[cfg(test)]
 pub mod tests;
 
+// One entry of a subdev spec: a daughterboard slot and a frontend on it, written `A:0` in markup
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SubdevPair {
+    pub db_name: String,
+    pub sd_name: String,
+}
+
+impl SubdevPair {
+
+    pub fn new(db_name: &str, sd_name: &str) -> Self {
+        Self { db_name: db_name.to_owned(), sd_name: sd_name.to_owned() }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.db_name.is_empty() {
+            return Err("Subdev pair needs a daughterboard name");
+        }
+        let bad_char = |s: &str| s.chars().any(|c| c.is_whitespace() || c == ':');
+        if bad_char(&self.db_name) || bad_char(&self.sd_name) {
+            return Err("Subdev names can't contain whitespace or ':'");
+        }
+        Ok(())
+    }
+
+    // A bare daughterboard name leaves the frontend up to the device, so it reads back as e.g. `A:0`
+    pub fn matches(&self, readback: &SubdevPair) -> bool {
+        self.db_name == readback.db_name && (self.sd_name.is_empty() || self.sd_name == readback.sd_name)
+    }
+
+}
+
+impl fmt::Display for SubdevPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.db_name, self.sd_name)
+    }
+}
+
+impl FromStr for SubdevPair {
+    type Err = &'static str;
+
+    // UHD accepts a bare daughterboard name and treats the frontend as empty
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (db_name, sd_name) = s.split_once(':').unwrap_or((s, ""));
+        let pair = Self::new(db_name, sd_name);
+        pair.validate()?;
+        Ok(pair)
+    }
+}
+
 pub struct SubdevSpec {
     pub handle: usize,
 }
@@ -44,6 +100,14 @@ impl SubdevSpec {
         }
     }
 
+    pub fn from_pairs(pairs: &[SubdevPair]) -> Result<Self, &'static str> {
+        for pair in pairs {
+            pair.validate()?;
+        }
+        let markup: Vec<String> = pairs.iter().map(|p| p.to_string()).collect();
+        Self::new(&markup.join(" "))
+    }
+
     pub fn last_error(&mut self) -> Result<String, &'static str> {
         unsafe {
             let mut err: Vec<u8> = vec![0x00; 128];
@@ -55,7 +119,7 @@ impl SubdevSpec {
         }
     }
 
-    pub fn to_string(&mut self) -> Result<String, &'static str> {
+    pub fn to_string(&self) -> Result<String, &'static str> {
         unsafe {
             let mut err: Vec<u8> = vec![0x00; 128];
             match uhd_subdev_spec_to_string(self.handle, err.as_mut_ptr(), err.len()) {
@@ -66,7 +130,17 @@ impl SubdevSpec {
         }
     }
 
-    pub fn len(&mut self) -> Result<usize, &'static str> {
+    pub fn to_pp_string(&self) -> Result<String, &'static str> {
+        unsafe {
+            let mut buff: Vec<u8> = vec![0x00; 1024];
+            match uhd_subdev_spec_to_pp_string(self.handle, buff.as_mut_ptr(), buff.len()) {
+                0 => Ok(collect_cstr(buff.as_ptr())),
+                _ => Err("Failed to get subdev spec as a pretty-print string"),
+            }
+        }
+    }
+
+    pub fn len(&self) -> Result<usize, &'static str> {
         let mut ans: isize = 0;
         unsafe {
             match uhd_subdev_spec_size(self.handle, &mut ans) {
@@ -76,6 +150,34 @@ impl SubdevSpec {
         }
     }
 
+    pub fn is_empty(&self) -> Result<bool, &'static str> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn at(&self, idx: usize) -> Result<SubdevPair, &'static str> {
+        let mut pair = SubdevSpecPairC { db_name: std::ptr::null_mut(), sd_name: std::ptr::null_mut() };
+        unsafe {
+            if uhd_subdev_spec_at(self.handle, idx, &mut pair) != 0 {
+                return Err("Nonzero return value in SubdevSpec::at");
+            }
+            // UHD allocates both names; copy them out before handing them back to be freed
+            let owned = |ptr: *mut c_char| if ptr.is_null() {
+                Ok(String::new())
+            } else {
+                CStr::from_ptr(ptr).to_str().map(|s| s.to_owned()).map_err(|_| "Bad UTF-8")
+            };
+            let ans = owned(pair.db_name).and_then(|db_name| Ok(SubdevPair{ db_name, sd_name: owned(pair.sd_name)? }));
+            if uhd_subdev_spec_pair_free(&mut pair) != 0 {
+                eprintln!("WARN: Nonzero return value in uhd_subdev_spec_pair_free");
+            }
+            ans
+        }
+    }
+
+    pub fn pairs(&self) -> Result<Vec<SubdevPair>, &'static str> {
+        (0..self.len()?).map(|idx| self.at(idx)).collect()
+    }
+
     pub fn push_back(&mut self, markup: &str) -> Result<(), &'static str> {
         let markup_c = CString::new(markup).map_err(|_| "Unable to build CString from subdev spec markup")?;
         unsafe {
@@ -86,6 +188,32 @@ impl SubdevSpec {
         }
     }
 
+    pub fn push_pair(&mut self, pair: &SubdevPair) -> Result<(), &'static str> {
+        pair.validate()?;
+        self.push_back(&pair.to_string())
+    }
+
+    // Whether `readback` is what a device that was given this spec should report
+    pub fn matches(&self, readback: &SubdevSpec) -> Result<bool, &'static str> {
+        let (pairs, readback) = (self.pairs()?, readback.pairs()?);
+        Ok(pairs.len() == readback.len() && pairs.iter().zip(readback.iter()).all(|(p, r)| p.matches(r)))
+    }
+
+    // Checks that don't need a device: at least one pair, each well formed, and no frontend listed twice
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let pairs = self.pairs()?;
+        if pairs.is_empty() {
+            return Err("Subdev spec is empty");
+        }
+        for (idx, pair) in pairs.iter().enumerate() {
+            pair.validate()?;
+            if pairs[..idx].contains(pair) {
+                return Err("Subdev spec lists the same frontend twice");
+            }
+        }
+        Ok(())
+    }
+
 }
 
 impl Drop for SubdevSpec {
@@ -96,4 +224,4 @@ impl Drop for SubdevSpec {
             }
         }
     }
-}
\ No newline at end of file
+}
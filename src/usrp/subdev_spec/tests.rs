@@ -1,4 +1,4 @@
-use crate::usrp::subdev_spec::SubdevSpec;
+use crate::usrp::subdev_spec::{SubdevPair, SubdevSpec};
 
 #[test]
 fn subdev_spec_basic() -> Result<(), &'static str> {
@@ -10,4 +10,26 @@ fn subdev_spec_basic() -> Result<(), &'static str> {
     assert_eq!(2, s.len()?);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn subdev_pair_markup() {
+    let pair:SubdevPair = "B:0".parse().unwrap();
+    assert_eq!(SubdevPair::new("B", "0"), pair);
+    assert_eq!("B:0", pair.to_string());
+
+    let bare:SubdevPair = "A".parse().unwrap();
+    assert_eq!("", bare.sd_name);
+
+    assert!(":0".parse::<SubdevPair>().is_err());
+    assert!("A:0 B:0".parse::<SubdevPair>().is_err());
+    assert!(SubdevPair::new("A", "0:1").validate().is_err());
+}
+
+#[test]
+fn subdev_pair_readback() {
+    assert!(SubdevPair::new("A", "").matches(&SubdevPair::new("A", "0")));
+    assert!(SubdevPair::new("A", "0").matches(&SubdevPair::new("A", "0")));
+    assert!(!SubdevPair::new("A", "0").matches(&SubdevPair::new("A", "1")));
+    assert!(!SubdevPair::new("A", "").matches(&SubdevPair::new("B", "0")));
+}